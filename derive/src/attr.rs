@@ -1,9 +1,22 @@
-use proc_macro2::Span;
+use std::collections::BTreeSet;
+
+use proc_macro2::{Span, TokenStream, TokenTree};
+use quote::{ToTokens, quote};
+use syn::ext::IdentExt;
 use syn::meta::ParseNestedMeta;
 use syn::parse::Parse;
-use syn::{Attribute, Error, Field, ItemEnum, ItemStruct, Lit, LitStr, Meta, Result, Variant};
-
-use crate::opts::{ContainerOpts, FieldOpts, Modifier, OptionSet, VariantOpts};
+use syn::punctuated::Punctuated;
+use syn::spanned::Spanned;
+use syn::{
+    Attribute, Data, DeriveInput, Error, ExprPath, Field, Fields, GenericArgument, Index, Lifetime,
+    LitStr, Member, Path, PathArguments, Result, Token, Type, Variant, WherePredicate,
+};
+
+use crate::bound;
+use crate::opts::{
+    self, Case, ContainerOpts, Errors, FieldOpts, Modifier, OptionSet, Repr, SpannedModifier,
+    TagStyle, VariantOpts, With,
+};
 
 fn parse_lit_mod<T>(meta: ParseNestedMeta, f: impl FnOnce(String) -> T) -> Result<T> {
     let value = meta.value()?;
@@ -11,34 +24,92 @@ fn parse_lit_mod<T>(meta: ParseNestedMeta, f: impl FnOnce(String) -> T) -> Resul
     Ok(f(data.value()))
 }
 
-fn parse_modifier(meta: ParseNestedMeta) -> Result<Modifier> {
+// Like `parse_lit_mod`, but checks that the literal holds a `P` so that
+// mistakes are reported at the attribute.
+fn parse_checked_mod<P: Parse, T>(meta: ParseNestedMeta, f: impl FnOnce(String) -> T) -> Result<T> {
+    let value = meta.value()?;
+    let data: LitStr = value.parse()?;
+    data.parse::<P>()?;
+    Ok(f(data.value()))
+}
+
+// Parses either `name = "..."`, which applies to both directions, or
+// `name(serialize = "...", deserialize = "...")` with either side optional.
+fn parse_ser_de(meta: ParseNestedMeta) -> Result<(Option<LitStr>, Option<LitStr>)> {
+    if meta.input.peek(Token![=]) {
+        let lit: LitStr = meta.value()?.parse()?;
+        return Ok((Some(lit.clone()), Some(lit)));
+    }
+
+    let mut serialize = None;
+    let mut deserialize = None;
+    meta.parse_nested_meta(|meta| {
+        if meta.path.is_ident("serialize") {
+            serialize = Some(meta.value()?.parse()?);
+            Ok(())
+        } else if meta.path.is_ident("deserialize") {
+            deserialize = Some(meta.value()?.parse()?);
+            Ok(())
+        } else {
+            Err(meta.error("expected `serialize` or `deserialize`"))
+        }
+    })?;
+    Ok((serialize, deserialize))
+}
+
+// Checks that the literal holds comma separated where-clause predicates, so
+// that mistakes are reported at the attribute.
+fn parse_bound(lit: LitStr) -> Result<String> {
+    lit.parse_with(Punctuated::<WherePredicate, Token![,]>::parse_terminated)?;
+    Ok(lit.value())
+}
+
+// Checks that the literal holds lifetimes separated by `+`, so that mistakes
+// are reported at the attribute.
+fn parse_borrow(lit: LitStr) -> Result<String> {
+    lit.parse_with(Punctuated::<Lifetime, Token![+]>::parse_separated_nonempty)?;
+    Ok(lit.value())
+}
+
+fn parse_case(lit: LitStr) -> Result<Case> {
+    lit.value()
+        .parse()
+        .map_err(|err: String| Error::new(lit.span(), err))
+}
+
+fn parse_repr(lit: LitStr) -> Result<Repr> {
+    lit.value()
+        .parse()
+        .map_err(|err: String| Error::new(lit.span(), err))
+}
+
+// `namespace` is the attribute the modifier is written in, since settings
+// which serde does not have are only accepted under `qser`.
+fn parse_modifier(meta: ParseNestedMeta, namespace: &str) -> Result<Modifier> {
     if meta.path.is_ident("rename") {
-        let value = meta.value()?;
-        let name: LitStr = value.parse()?;
+        let (serialize, deserialize) = parse_ser_de(meta)?;
 
         return Ok(Modifier::Rename {
-            serialize_name: Some(name.value()),
-            deserialize_name: Some(name.value()),
+            serialize_name: serialize.map(|name| name.value()),
+            deserialize_name: deserialize.map(|name| name.value()),
         });
     }
 
     if meta.path.is_ident("rename_all") {
-        let value = meta.value()?;
-        let name: LitStr = value.parse()?;
+        let (serialize, deserialize) = parse_ser_de(meta)?;
 
-        return Ok(Modifier::Rename {
-            serialize_name: Some(name.value()),
-            deserialize_name: Some(name.value()),
+        return Ok(Modifier::RenameAll {
+            serialize_case: serialize.map(parse_case).transpose()?,
+            deserialize_case: deserialize.map(parse_case).transpose()?,
         });
     }
 
     if meta.path.is_ident("rename_all_fields") {
-        let value = meta.value()?;
-        let name: LitStr = value.parse()?;
+        let (serialize, deserialize) = parse_ser_de(meta)?;
 
-        return Ok(Modifier::Rename {
-            serialize_name: Some(name.value()),
-            deserialize_name: Some(name.value()),
+        return Ok(Modifier::RenameAllFields {
+            serialize_case: serialize.map(parse_case).transpose()?,
+            deserialize_case: deserialize.map(parse_case).transpose()?,
         });
     }
 
@@ -58,16 +129,36 @@ fn parse_modifier(meta: ParseNestedMeta) -> Result<Modifier> {
         return Ok(Modifier::Untagged);
     }
 
+    if meta.path.is_ident("repr") {
+        if namespace != "qser" {
+            return Err(meta.error("`repr` is only supported in #[qser(...)]"));
+        }
+        let lit: LitStr = meta.value()?.parse()?;
+        return Ok(Modifier::Repr {
+            repr: parse_repr(lit)?,
+        });
+    }
+
     if meta.path.is_ident("bound") {
-        todo!();
+        let (serialize, deserialize) = parse_ser_de(meta)?;
+
+        return Ok(Modifier::Bound {
+            serialize: serialize.map(parse_bound).transpose()?,
+            deserialize: deserialize.map(parse_bound).transpose()?,
+        });
     }
 
     if meta.path.is_ident("default") {
+        if meta.input.peek(Token![=]) {
+            return parse_checked_mod::<ExprPath, _>(meta, |value| Modifier::Default {
+                item: Some(value),
+            });
+        }
         return Ok(Modifier::Default { item: None });
     }
 
     if meta.path.is_ident("remote") {
-        return parse_lit_mod(meta, |value| Modifier::Remote { item: value });
+        return parse_checked_mod::<Path, _>(meta, |value| Modifier::Remote { item: value });
     }
 
     if meta.path.is_ident("transparent") {
@@ -75,19 +166,19 @@ fn parse_modifier(meta: ParseNestedMeta) -> Result<Modifier> {
     }
 
     if meta.path.is_ident("from") {
-        return parse_lit_mod(meta, |value| Modifier::From { item: value });
+        return parse_checked_mod::<Type, _>(meta, |value| Modifier::From { item: value });
     }
 
     if meta.path.is_ident("try_from") {
-        return parse_lit_mod(meta, |value| Modifier::TryFrom { item: value });
+        return parse_checked_mod::<Type, _>(meta, |value| Modifier::TryFrom { item: value });
     }
 
     if meta.path.is_ident("into") {
-        return parse_lit_mod(meta, |value| Modifier::Into { item: value });
+        return parse_checked_mod::<Type, _>(meta, |value| Modifier::Into { item: value });
     }
 
     if meta.path.is_ident("crate") {
-        return parse_lit_mod(meta, |value| Modifier::Crate { path: value });
+        return parse_checked_mod::<Path, _>(meta, |value| Modifier::Crate { path: value });
     }
 
     if meta.path.is_ident("expecting") {
@@ -119,18 +210,28 @@ fn parse_modifier(meta: ParseNestedMeta) -> Result<Modifier> {
     }
 
     if meta.path.is_ident("serialize_with") {
-        return parse_lit_mod(meta, |value| Modifier::SerializeWith { imp: value });
+        return parse_checked_mod::<ExprPath, _>(meta, |value| Modifier::SerializeWith {
+            imp: value,
+        });
     }
 
     if meta.path.is_ident("deserialize_with") {
-        return parse_lit_mod(meta, |value| Modifier::DeserializeWith { imp: value });
+        return parse_checked_mod::<ExprPath, _>(meta, |value| Modifier::DeserializeWith {
+            imp: value,
+        });
     }
 
     if meta.path.is_ident("with") {
-        return parse_lit_mod(meta, |value| Modifier::With { imp: value });
+        return parse_checked_mod::<ExprPath, _>(meta, |value| Modifier::With { imp: value });
     }
 
     if meta.path.is_ident("borrow") {
+        if meta.input.peek(Token![=]) {
+            let lit: LitStr = meta.value()?.parse()?;
+            return Ok(Modifier::Borrow {
+                li: Some(parse_borrow(lit)?),
+            });
+        }
         return Ok(Modifier::Borrow { li: None });
     }
 
@@ -143,69 +244,521 @@ fn parse_modifier(meta: ParseNestedMeta) -> Result<Modifier> {
     }
 
     if meta.path.is_ident("skip_serializing_if") {
-        return parse_lit_mod(meta, |value| Modifier::SkipSerializingIf { imp: value });
+        return parse_checked_mod::<ExprPath, _>(meta, |value| Modifier::SkipSerializingIf {
+            imp: value,
+        });
     }
 
     if meta.path.is_ident("getter") {
-        return parse_lit_mod(meta, |value| Modifier::Getter { item: value });
+        return parse_checked_mod::<ExprPath, _>(meta, |value| Modifier::Getter { item: value });
     }
 
-    Err(Error::new(Span::call_site(), "unsupported attribute"))
+    let name = meta.path.to_token_stream().to_string().replace(' ', "");
+    Err(meta.error(format!("unknown attribute `{}`", name)))
 }
 
-fn attr_modifiers(attrs: &[Attribute]) -> Result<Vec<Modifier>> {
+// Invalid modifiers are reported to `errors` and skipped, so that the rest of
+// the attribute is still checked.
+fn attr_namespace_modifiers(
+    attrs: &[Attribute],
+    namespace: &str,
+    errors: &mut Errors,
+) -> Vec<SpannedModifier> {
     let mut modifiers = Vec::new();
 
     for attr in attrs {
-        if !attr.path().is_ident("serde") {
+        if !attr.path().is_ident(namespace) {
             continue;
         }
 
-        attr.parse_nested_meta(|meta| {
-            modifiers.push(parse_modifier(meta)?);
+        let parsed = attr.parse_nested_meta(|meta| {
+            let span = meta.path.span();
+            let input = meta.input;
+            match parse_modifier(meta, namespace) {
+                Ok(modifier) => modifiers.push(SpannedModifier { modifier, span }),
+                Err(err) => {
+                    errors.push(err);
+                    while !input.is_empty() && !input.peek(Token![,]) {
+                        input.parse::<TokenTree>()?;
+                    }
+                }
+            }
             Ok(())
-        })?;
+        });
+        errors.check(parsed);
     }
 
-    Ok(modifiers)
+    modifiers
 }
 
-pub fn attr_struct_opts(item: &ItemStruct) -> Result<ContainerOpts> {
-    let mut opts = ContainerOpts::default();
-    let modifiers = attr_modifiers(&item.attrs)?;
-    opts.apply_modifiers(&modifiers).unwrap();
-    Ok(opts)
+// Modifiers are read from both `#[serde(...)]` and `#[qser(...)]`, so that a
+// type deriving both libraries' traits can configure each separately. A
+// setting given under `qser` replaces the same setting given under `serde`,
+// e.g. `#[qser(untagged)]` replaces `#[serde(tag = "...")]`. Settings which
+// differ between serializing and deserializing are replaced one side at a
+// time, so `#[qser(serialize_with = "...")]` keeps the deserializing side of
+// `#[serde(with = "...")]`.
+fn attr_modifiers(attrs: &[Attribute], errors: &mut Errors) -> Vec<SpannedModifier> {
+    let serde = attr_namespace_modifiers(attrs, "serde", errors);
+    let overrides = attr_namespace_modifiers(attrs, "qser", errors);
+    let mut modifiers = Vec::new();
+    for SpannedModifier { modifier, span } in serde {
+        let (mut serialize, mut deserialize) = modifier.sides();
+        for o in &overrides {
+            if o.modifier.setting() == modifier.setting() {
+                let (ser, de) = o.modifier.sides();
+                serialize &= !ser;
+                deserialize &= !de;
+            }
+        }
+        if (serialize, deserialize) == modifier.sides() {
+            modifiers.push(SpannedModifier { modifier, span });
+        } else if serialize || deserialize {
+            let modifier = modifier.restrict(serialize, deserialize);
+            modifiers.push(SpannedModifier { modifier, span });
+        }
+    }
+    modifiers.extend(overrides);
+    modifiers
 }
 
-pub fn attr_enum_opts(item: &ItemEnum) -> Result<ContainerOpts> {
-    let mut opts = ContainerOpts::default();
-    let modifiers = attr_modifiers(&item.attrs)?;
-    opts.apply_modifiers(&modifiers).unwrap();
+fn attr_opts<T: OptionSet + Default>(attrs: &[Attribute]) -> Result<T> {
+    let mut errors = Errors::new();
+    let mut opts = T::default();
+    let modifiers = attr_modifiers(attrs, &mut errors);
+    errors.check(opts.apply_modifiers(&modifiers));
+    errors.finish()?;
     Ok(opts)
 }
 
+pub fn attr_container_opts(input: &DeriveInput) -> Result<ContainerOpts> {
+    attr_opts(&input.attrs)
+}
+
 pub fn attr_variant_opts(variant: &Variant) -> Result<VariantOpts> {
-    let mut opts = VariantOpts::default();
-    let modifiers = attr_modifiers(&variant.attrs)?;
-    opts.apply_modifiers(&modifiers).unwrap();
-    Ok(opts)
+    attr_opts(&variant.attrs)
 }
 
 pub fn attr_field_opts(field: &Field) -> Result<FieldOpts> {
-    let mut opts = FieldOpts::default();
-    let modifiers = attr_modifiers(&field.attrs)?;
-    opts.apply_modifiers(&modifiers).unwrap();
-    Ok(opts)
+    attr_opts(&field.attrs)
+}
+
+// Checks the attributes of the container and everything in it up front, so
+// that all of their errors are reported together.
+pub fn check_attrs(input: &DeriveInput) -> Result<()> {
+    let mut errors = Errors::new();
+    errors.check(attr_container_opts(input));
+    // Parse errors are already reported along with the options.
+    let modifiers = attr_modifiers(&input.attrs, &mut Errors::new());
+    match &input.data {
+        Data::Struct(data) => {
+            // Only named fields have names for `rename_all` or map entries
+            // that could be unknown or missing.
+            let (position, names): (_, &[&str]) = match &data.fields {
+                Fields::Named(_) => ("structs", &[]),
+                Fields::Unnamed(fields) if fields.unnamed.len() == 1 => (
+                    "newtype structs",
+                    &["rename_all", "deny_unknown_fields", "default"],
+                ),
+                Fields::Unnamed(_) => ("tuple structs", &["rename_all", "deny_unknown_fields"]),
+                Fields::Unit => (
+                    "unit structs",
+                    &["rename_all", "deny_unknown_fields", "default"],
+                ),
+            };
+            let tagging = ["tag", "content", "untagged", "rename_all_fields"];
+            opts::reject(&mut errors, &modifiers, &tagging, position);
+            opts::reject(&mut errors, &modifiers, names, position);
+            check_field_attrs(&data.fields, &mut errors);
+        }
+        Data::Enum(data) => {
+            opts::reject(
+                &mut errors,
+                &modifiers,
+                &["transparent", "default"],
+                "enums",
+            );
+            // Unknown fields are denied within struct variants and around
+            // the content of adjacently tagged enums.
+            let adjacent = modifiers.iter().any(|m| m.modifier.name() == "content");
+            let has_struct = data
+                .variants
+                .iter()
+                .any(|variant| matches!(variant.fields, Fields::Named(_)));
+            if !adjacent && !has_struct {
+                let position = "enums without struct variants";
+                opts::reject(&mut errors, &modifiers, &["deny_unknown_fields"], position);
+            }
+            for variant in &data.variants {
+                errors.check(attr_variant_opts(variant));
+                let modifiers = attr_modifiers(&variant.attrs, &mut Errors::new());
+                match &variant.fields {
+                    Fields::Named(_) => {}
+                    Fields::Unnamed(_) => {
+                        opts::reject(&mut errors, &modifiers, &["rename_all"], "tuple variants");
+                    }
+                    Fields::Unit => {
+                        opts::reject(&mut errors, &modifiers, &["rename_all"], "unit variants");
+                    }
+                }
+                check_field_attrs(&variant.fields, &mut errors);
+            }
+        }
+        Data::Union(_) => {}
+    }
+    errors.finish()
+}
+
+// A flattened field is read from and written into the entries of its
+// container's map, which cannot be shared between several flattened fields.
+fn check_field_attrs(fields: &Fields, errors: &mut Errors) {
+    let mut flattened = false;
+    for field in fields {
+        let opts = attr_field_opts(field);
+        if let Ok(opts) = &opts
+            && opts.flatten
+        {
+            if is_non_map(&field.ty) {
+                errors.push(Error::new_spanned(
+                    &field.ty,
+                    "a flattened field must be a map",
+                ));
+            }
+            if flattened {
+                errors.push(Error::new_spanned(
+                    field,
+                    "at most one field can be flattened",
+                ));
+            }
+            flattened = true;
+        }
+        errors.check(opts);
+        if field.ident.is_none() {
+            let modifiers = attr_modifiers(&field.attrs, &mut Errors::new());
+            let names = ["rename", "alias", "flatten"];
+            opts::reject(errors, &modifiers, &names, "tuple fields");
+        }
+    }
+}
+
+// The key of a field in one direction, given its `rename` and the
+// `rename_all` rule of its container for that direction.
+pub fn name_of_field(field: &Field, rename: Option<&String>, rename_all: Option<&Case>) -> String {
+    rename.cloned().unwrap_or_else(|| {
+        let name = field.ident.as_ref().unwrap().unraw().to_string();
+        match rename_all {
+            Some(case) => case.apply_to_field(&name),
+            None => name,
+        }
+    })
+}
+
+pub fn name_of_variant(
+    variant: &Variant,
+    rename: Option<&String>,
+    rename_all: Option<&Case>,
+) -> String {
+    rename.cloned().unwrap_or_else(|| {
+        let name = variant.ident.unraw().to_string();
+        match rename_all {
+            Some(case) => case.apply_to_variant(&name),
+            None => name,
+        }
+    })
+}
+
+pub fn parse_path(path: &str) -> Result<ExprPath> {
+    syn::parse_str(path)
+        .map_err(|_| Error::new(Span::call_site(), format!("invalid path: {}", path)))
+}
+
+pub fn serialize_hook(with: &With) -> Result<Option<ExprPath>> {
+    match (&with.serialize_fn, &with.module) {
+        (Some(path), _) => parse_path(path).map(Some),
+        (None, Some(module)) => parse_path(&format!("{}::serialize", module)).map(Some),
+        (None, None) => Ok(None),
+    }
+}
+
+pub fn deserialize_hook(with: &With) -> Result<Option<ExprPath>> {
+    match (&with.deserialize_fn, &with.module) {
+        (Some(path), _) => parse_path(path).map(Some),
+        (None, Some(module)) => parse_path(&format!("{}::deserialize", module)).map(Some),
+        (None, None) => Ok(None),
+    }
+}
+
+pub fn member_of_field(index: usize, field: &Field) -> Member {
+    match &field.ident {
+        Some(ident) => Member::Named(ident.clone()),
+        None => Member::Unnamed(Index::from(index)),
+    }
 }
 
-pub fn name_of_field(field: &Field, opts: &FieldOpts) -> String {
-    opts.rename
-        .clone()
-        .unwrap_or_else(|| field.ident.as_ref().unwrap().to_string())
+pub fn parse_bound_predicates(predicates: &str) -> Result<Punctuated<WherePredicate, Token![,]>> {
+    syn::parse::Parser::parse_str(Punctuated::parse_terminated, predicates)
+        .map_err(|_| Error::new(Span::call_site(), format!("invalid bound: {}", predicates)))
+}
+
+// The path through which generated code refers to qser, `qser` unless
+// overridden by `#[serde(crate = "...")]`.
+pub fn crate_path(input: &DeriveInput) -> Result<Path> {
+    let container = attr_container_opts(input)?;
+    match &container.crate_path {
+        Some(path) => syn::parse_str(path)
+            .map_err(|_| Error::new(Span::call_site(), format!("invalid path: {}", path))),
+        None => Ok(syn::parse_quote!(qser)),
+    }
+}
+
+// The type that derived code reads and writes, along with the path naming it
+// in constructors and patterns: the input itself, or the type it mirrors when
+// `#[serde(remote = "...")]` is given.
+pub fn this_type(input: &DeriveInput) -> Result<(TokenStream, TokenStream)> {
+    let container = attr_container_opts(input)?;
+    let ident = &input.ident;
+    let (_, ty_generics, _) = input.generics.split_for_impl();
+    let Some(remote) = &container.remote else {
+        return Ok((quote!(#ident #ty_generics), quote!(#ident)));
+    };
+
+    let mut path: Path = syn::parse_str(remote)
+        .map_err(|_| Error::new(Span::call_site(), format!("invalid path: {}", remote)))?;
+    let ty = if path.segments.last().unwrap().arguments.is_none() {
+        quote!(#path #ty_generics)
+    } else {
+        quote!(#path)
+    };
+    path.segments.last_mut().unwrap().arguments = PathArguments::None;
+    Ok((ty, quote!(#path)))
+}
+
+pub fn has_getter<'a>(fields: impl IntoIterator<Item = &'a Field>) -> Result<bool> {
+    for field in fields {
+        if attr_field_opts(field)?.getter.is_some() {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+// Getters stand in for the fields of a remote struct which the mirror cannot
+// access, and are meaningless anywhere else.
+pub fn check_getters(input: &DeriveInput, container: &ContainerOpts) -> Result<()> {
+    let fields: Vec<&Field> = match &input.data {
+        Data::Struct(_) if container.remote.is_some() => return Ok(()),
+        Data::Struct(data) => data.fields.iter().collect(),
+        Data::Enum(data) => data.variants.iter().flat_map(|v| &v.fields).collect(),
+        Data::Union(_) => Vec::new(),
+    };
+    for field in fields {
+        if attr_field_opts(field)?.getter.is_some() {
+            return Err(Error::new_spanned(
+                field,
+                "`getter` is only allowed on fields of remote structs",
+            ));
+        }
+    }
+    Ok(())
+}
+
+// The lifetimes of a field that deserialized values borrow from the input:
+// those named by `#[serde(borrow)]`, or implicitly the lifetime of a `&str`.
+pub fn borrowed_lifetimes_of_field(field: &Field) -> Result<BTreeSet<Lifetime>> {
+    let opts = attr_field_opts(field)?;
+    if opts.skip.deserializing {
+        return Ok(BTreeSet::new());
+    }
+    if !opts.borrow.on {
+        return Ok(match &field.ty {
+            Type::Reference(ty) if is_str(&ty.elem) => bound::lifetimes_of_type(&field.ty),
+            _ => BTreeSet::new(),
+        });
+    }
+
+    let all = bound::lifetimes_of_type(&field.ty);
+    if all.is_empty() {
+        return Err(Error::new_spanned(
+            &field.ty,
+            "field has no lifetimes to borrow",
+        ));
+    }
+    let Some(lifetimes) = &opts.borrow.lifetimes else {
+        return Ok(all);
+    };
+    let parser = Punctuated::<Lifetime, Token![+]>::parse_separated_nonempty;
+    let lifetimes = syn::parse::Parser::parse_str(parser, lifetimes).map_err(|_| {
+        Error::new(
+            Span::call_site(),
+            format!("invalid lifetimes: {}", lifetimes),
+        )
+    })?;
+    let mut borrowed = BTreeSet::new();
+    for lifetime in lifetimes {
+        if !all.contains(&lifetime) {
+            return Err(Error::new_spanned(
+                &field.ty,
+                format!("field type does not have lifetime {}", lifetime),
+            ));
+        }
+        borrowed.insert(lifetime);
+    }
+    Ok(borrowed)
+}
+
+pub fn borrowed_lifetimes(input: &DeriveInput) -> Result<BTreeSet<Lifetime>> {
+    let fields: Vec<&Field> = match &input.data {
+        Data::Struct(data) => data.fields.iter().collect(),
+        Data::Enum(data) => data.variants.iter().flat_map(|v| &v.fields).collect(),
+        Data::Union(_) => Vec::new(),
+    };
+    let mut borrowed = BTreeSet::new();
+    for field in fields {
+        borrowed.extend(borrowed_lifetimes_of_field(field)?);
+    }
+    Ok(borrowed)
+}
+
+fn is_str(ty: &Type) -> bool {
+    matches!(ty, Type::Path(ty) if ty.qself.is_none() && ty.path.is_ident("str"))
+}
+
+// Whether the type is `Cow<str>`, which can hold either a string borrowed
+// from the input or a copy.
+pub fn is_cow_str(ty: &Type) -> bool {
+    let Type::Path(ty) = ty else {
+        return false;
+    };
+    let Some(segment) = ty.path.segments.last() else {
+        return false;
+    };
+    let PathArguments::AngleBracketed(args) = &segment.arguments else {
+        return false;
+    };
+    segment.ident == "Cow"
+        && args
+            .args
+            .iter()
+            .any(|arg| matches!(arg, GenericArgument::Type(ty) if is_str(ty)))
+}
+
+// Variants written as their discriminant cannot carry a payload.
+pub fn check_repr(input: &DeriveInput, container: &ContainerOpts) -> Result<()> {
+    if container.repr != Repr::Int {
+        return Ok(());
+    }
+    let Data::Enum(data) = &input.data else {
+        return Err(Error::new(
+            Span::call_site(),
+            "`repr = \"int\"` is only supported on enums",
+        ));
+    };
+    for variant in &data.variants {
+        if !matches!(variant.fields, Fields::Unit) {
+            return Err(Error::new_spanned(
+                variant,
+                "`repr = \"int\"` requires every variant to be a unit variant",
+            ));
+        }
+    }
+    Ok(())
+}
+
+// Whether the type is known by its name to serialize as something other than
+// a map or null, such as a number, a string or a sequence. Any other type may
+// serialize as a map, which is only known once it is serialized.
+pub fn is_non_map(ty: &Type) -> bool {
+    match ty {
+        Type::Reference(ty) => is_non_map(&ty.elem),
+        Type::Paren(ty) => is_non_map(&ty.elem),
+        Type::Group(ty) => is_non_map(&ty.elem),
+        Type::Array(_) | Type::Slice(_) => true,
+        Type::Tuple(ty) => !ty.elems.is_empty(),
+        Type::Path(ty) if ty.qself.is_none() => {
+            let Some(segment) = ty.path.segments.last() else {
+                return false;
+            };
+            match segment.ident.to_string().as_str() {
+                "bool" | "char" | "str" | "String" | "i8" | "i16" | "i32" | "i64" | "i128"
+                | "isize" | "u8" | "u16" | "u32" | "u64" | "u128" | "usize" | "f32" | "f64"
+                | "Vec" | "VecDeque" | "LinkedList" | "BinaryHeap" | "BTreeSet" | "HashSet" => true,
+                "Box" => match &segment.arguments {
+                    PathArguments::AngleBracketed(args) => args
+                        .args
+                        .iter()
+                        .any(|arg| matches!(arg, GenericArgument::Type(ty) if is_non_map(ty))),
+                    _ => false,
+                },
+                _ => false,
+            }
+        }
+        _ => false,
+    }
+}
+
+// The content of an internally tagged variant is written into the same map as
+// the tag, so it has to be a map, and none of its keys can be the tag.
+pub fn check_internal_tag(input: &DeriveInput, container: &ContainerOpts) -> Result<()> {
+    let (TagStyle::Internal { field: tag }, Data::Enum(data)) = (&container.tag_style, &input.data)
+    else {
+        return Ok(());
+    };
+    for variant in &data.variants {
+        match &variant.fields {
+            Fields::Unnamed(fields) if fields.unnamed.len() == 1 => {
+                let field = &fields.unnamed[0];
+                let opts = attr_field_opts(field)?;
+                if opts.with.module.is_none()
+                    && opts.with.serialize_fn.is_none()
+                    && is_non_map(&field.ty)
+                {
+                    return Err(Error::new_spanned(
+                        &field.ty,
+                        "the content of an internally tagged variant must be a map",
+                    ));
+                }
+            }
+            Fields::Named(fields) => {
+                let variant_opts = attr_variant_opts(variant)?;
+                for field in &fields.named {
+                    let opts = attr_field_opts(field)?;
+                    if opts.flatten {
+                        continue;
+                    }
+                    let serialize = !opts.skip.serializing
+                        && name_of_field(
+                            field,
+                            opts.rename.serialize.as_ref(),
+                            variant_opts
+                                .rename_all
+                                .serialize
+                                .as_ref()
+                                .or(container.rename_all_fields.serialize.as_ref()),
+                        ) == *tag;
+                    let deserialize = !opts.skip.deserializing
+                        && name_of_field(
+                            field,
+                            opts.rename.deserialize.as_ref(),
+                            variant_opts
+                                .rename_all
+                                .deserialize
+                                .as_ref()
+                                .or(container.rename_all_fields.deserialize.as_ref()),
+                        ) == *tag;
+                    if serialize || deserialize || opts.aliases.contains(tag) {
+                        return Err(Error::new_spanned(
+                            field,
+                            format!("field name `{}` is already used by the tag", tag),
+                        ));
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(())
 }
 
-pub fn name_of_variant(variant: &Variant, opts: &VariantOpts) -> String {
-    opts.rename
-        .clone()
-        .unwrap_or_else(|| variant.ident.to_string())
+pub fn parse_type(ty: &str) -> Result<Type> {
+    syn::parse_str(ty).map_err(|_| Error::new(Span::call_site(), format!("invalid type: {}", ty)))
 }
@@ -1,8 +1,11 @@
-use proc_macro2::{Span, TokenStream};
+use std::collections::BTreeSet;
+
+use proc_macro2::{Span, TokenStream, TokenTree};
+use quote::ToTokens;
 use syn::punctuated::Punctuated;
 use syn::{
-    GenericParam, Generics, Lifetime, LifetimeParam, TypeParamBound, WhereClause, WherePredicate,
-    parse_quote,
+    GenericParam, Generics, Lifetime, LifetimeParam, Type, TypeParamBound, WhereClause,
+    WherePredicate, parse_quote,
 };
 
 pub fn with_lifetime_bound(generics: &Generics, lifetime: &str) -> Generics {
@@ -36,6 +39,54 @@ pub fn with_lifetime_bound(generics: &Generics, lifetime: &str) -> Generics {
     }
 }
 
+// Unlike `with_lifetime_bound`, leaves the lifetime unconstrained so that it
+// stays late-bound in function signatures.
+pub fn with_lifetime(generics: &Generics, lifetime: &str) -> Generics {
+    let lifetime = Lifetime::new(lifetime, Span::call_site());
+    let mut generics = generics.clone();
+    generics
+        .params
+        .insert(0, GenericParam::Lifetime(LifetimeParam::new(lifetime)));
+    generics
+}
+
+// Prepends the `'de` lifetime of `Deserialize<'de>`, which outlives every
+// lifetime borrowed from the input.
+pub fn with_de_lifetime(generics: &Generics, borrowed: &BTreeSet<Lifetime>) -> Generics {
+    let mut def = LifetimeParam::new(Lifetime::new("'de", Span::call_site()));
+    def.bounds.extend(borrowed.iter().cloned());
+    let mut generics = generics.clone();
+    generics.params.insert(0, GenericParam::Lifetime(def));
+    generics
+}
+
+// The named lifetimes appearing anywhere in `ty`, other than `'static`.
+pub fn lifetimes_of_type(ty: &Type) -> BTreeSet<Lifetime> {
+    fn collect(tokens: TokenStream, lifetimes: &mut BTreeSet<Lifetime>) {
+        let mut tokens = tokens.into_iter();
+        while let Some(token) = tokens.next() {
+            match token {
+                TokenTree::Punct(punct) if punct.as_char() == '\'' => {
+                    if let Some(TokenTree::Ident(ident)) = tokens.next()
+                        && ident != "static"
+                    {
+                        lifetimes.insert(Lifetime {
+                            apostrophe: punct.span(),
+                            ident,
+                        });
+                    }
+                }
+                TokenTree::Group(group) => collect(group.stream(), lifetimes),
+                _ => {}
+            }
+        }
+    }
+
+    let mut lifetimes = BTreeSet::new();
+    collect(ty.to_token_stream(), &mut lifetimes);
+    lifetimes
+}
+
 pub fn where_clause_with_bound(generics: &Generics, bound: TokenStream) -> WhereClause {
     let new_predicates = generics.type_params().map::<WherePredicate, _>(|param| {
         let param = &param.ident;
@@ -49,3 +100,12 @@ pub fn where_clause_with_bound(generics: &Generics, bound: TokenStream) -> Where
         .extend(new_predicates);
     generics.where_clause.unwrap()
 }
+
+pub fn where_clause_with_predicates(
+    generics: &Generics,
+    predicates: impl IntoIterator<Item = WherePredicate>,
+) -> WhereClause {
+    let mut generics = generics.clone();
+    generics.make_where_clause().predicates.extend(predicates);
+    generics.where_clause.unwrap()
+}
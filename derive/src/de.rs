@@ -1,108 +1,607 @@
 use proc_macro2::{Span, TokenStream};
-use quote::quote;
+use quote::{ToTokens, quote};
 use syn::{
-    Data, DataEnum, DataStruct, DeriveInput, Error, Fields, FieldsNamed, Ident, Result, parse_quote,
+    Data, DataEnum, DataStruct, DeriveInput, Error, Field, Fields, FieldsNamed, FieldsUnnamed,
+    Generics, Ident, Index, Result, Type, WhereClause, parse_quote,
 };
 
-use crate::{attr, bound};
+use crate::opts::{Case, ContainerOpts, DefaultValue, FieldOpts, Repr, TagStyle};
+use crate::{attr, bound, pretend};
 
 pub fn derive(input: DeriveInput) -> Result<TokenStream> {
+    attr::check_attrs(&input)?;
+    let container = attr::attr_container_opts(&input)?;
+    attr::check_getters(&input, &container)?;
+    attr::check_repr(&input, &container)?;
+    attr::check_internal_tag(&input, &container)?;
     match &input.data {
+        _ if container.from.is_some() || container.try_from.is_some() => {
+            derive_from(&input, &container)
+        }
+        Data::Struct(DataStruct { fields, .. })
+            if container.transparent
+                || fields.len() == 1 && matches!(fields, Fields::Unnamed(_)) =>
+        {
+            derive_transparent(&input, fields)
+        }
         Data::Struct(DataStruct {
             fields: Fields::Named(fields),
             ..
-        }) => derive_struct(&input, fields),
-        Data::Enum(enumeration) => derive_enum(&input, enumeration),
-        _ => Err(Error::new(
-            Span::call_site(),
-            "currently only structs with named fields are supported",
-        )),
+        }) => derive_struct(&input, &container, fields),
+        Data::Struct(DataStruct {
+            fields: Fields::Unnamed(fields),
+            ..
+        }) => derive_tuple_struct(&input, &container, fields),
+        Data::Struct(DataStruct {
+            fields: Fields::Unit,
+            ..
+        }) => derive_unit_struct(&input),
+        Data::Enum(enumeration) if container.repr == Repr::Int => {
+            derive_enum_int(&input, enumeration)
+        }
+        Data::Enum(enumeration) => derive_enum(&input, &container, enumeration),
+        Data::Union(_) => Err(Error::new(Span::call_site(), "unions are not supported")),
     }
 }
 
-pub fn derive_struct(input: &DeriveInput, fields: &FieldsNamed) -> Result<TokenStream> {
+pub fn derive_struct(
+    input: &DeriveInput,
+    container: &ContainerOpts,
+    fields: &FieldsNamed,
+) -> Result<TokenStream> {
     let ident = &input.ident;
-    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let (_, _, where_clause) = input.generics.split_for_impl();
+    let de_generics = de_generics(input)?;
+    let (de_impl_generics, de_ty_generics, _) = de_generics.split_for_impl();
     let dummy = Ident::new(
         &format!("_IMPL_MINIDESERIALIZE_FOR_{}", ident),
         Span::call_site(),
     );
+    let krate = attr::crate_path(input)?;
+    let (this, construct) = struct_construct(input, fields.named.iter())?;
 
-    let fieldname = fields.named.iter().map(|f| &f.ident).collect::<Vec<_>>();
-    let fieldty = fields.named.iter().map(|f| &f.ty);
-    let fieldstr = fields
-        .named
-        .iter()
-        .map(|field| {
-            let opts = attr::attr_field_opts(field)?;
-            let name = attr::name_of_field(field, &opts);
-            Ok(name)
-        })
-        .collect::<Result<Vec<_>>>()?;
-
-    let wrapper_generics = bound::with_lifetime_bound(&input.generics, "'__a");
-    let (wrapper_impl_generics, wrapper_ty_generics, _) = wrapper_generics.split_for_impl();
-    let bound = parse_quote!(qser::Deserialize);
-    let bounded_where_clause = bound::where_clause_with_bound(&input.generics, bound);
+    let state = Ident::new("__State", Span::call_site());
+    let (init, items) = map_builder(
+        input,
+        &state,
+        &construct,
+        fields,
+        container.rename_all.deserialize.as_ref(),
+        container.deny_unknown_fields,
+        &container.default,
+    )?;
+    let bounded_where_clause = de_where_clause_with_default(input, &container.default)?;
+    let visitor = Ident::new("__Visitor", Span::call_site());
+    let deserialize = deserialize_impl(input, &bounded_where_clause, cast_place(input, &visitor)?)?;
 
     Ok(quote! {
         #[allow(non_upper_case_globals)]
         #[allow(non_local_definitions)]
         const #dummy: () = {
+            use #krate as __qser;
+
             #[repr(C)]
-            struct __Visitor #impl_generics #where_clause {
-                __out: std::option::Option<#ident #ty_generics>,
+            struct #visitor #de_impl_generics #where_clause {
+                __out: std::option::Option<#this>,
+                __marker: std::marker::PhantomData<&'de ()>,
             }
 
-            impl #impl_generics qser::Deserialize for #ident #ty_generics #bounded_where_clause {
-                fn begin(__out: &mut std::option::Option<Self>) -> &mut dyn qser::de::Visitor {
-                    unsafe {
-                        &mut *{
-                            __out
-                            as *mut std::option::Option<Self>
-                            as *mut __Visitor #ty_generics
-                        }
-                    }
+            #deserialize
+
+            impl #de_impl_generics __qser::de::Visitor<'de> for #visitor #de_ty_generics #bounded_where_clause {
+                fn map(&mut self) -> __qser::Result<std::boxed::Box<dyn __qser::de::Map<'de> + '_>> {
+                    let __out = &mut self.__out;
+                    Ok(std::boxed::Box::new(#init))
                 }
             }
 
-            impl #impl_generics qser::de::Visitor for __Visitor #ty_generics #bounded_where_clause {
-                fn map(&mut self) -> qser::Result<std::boxed::Box<dyn qser::de::Map + '_>> {
-                    Ok(std::boxed::Box::new(__State {
-                        #(
-                            #fieldname: qser::Deserialize::default(),
-                        )*
-                        __out: &mut self.__out,
-                    }))
+            #items
+        };
+    })
+}
+
+// The where clause of generated impls: `T: Deserialize` for every type
+// parameter, unless replaced by `#[serde(bound = "...")]`.
+fn de_where_clause(input: &DeriveInput) -> Result<WhereClause> {
+    let container = attr::attr_container_opts(input)?;
+    Ok(match &container.bound.deserialize {
+        Some(predicates) => bound::where_clause_with_predicates(
+            &input.generics,
+            attr::parse_bound_predicates(predicates)?,
+        ),
+        None => {
+            let bound = quote!(__qser::Deserialize<'de>);
+            bound::where_clause_with_bound(&input.generics, bound)
+        }
+    })
+}
+
+fn de_where_clause_with_default(
+    input: &DeriveInput,
+    default: &DefaultValue,
+) -> Result<WhereClause> {
+    let (this, _) = attr::this_type(input)?;
+    let mut bounded_where_clause = de_where_clause(input)?;
+    if default.on && default.path.is_none() {
+        bounded_where_clause
+            .predicates
+            .push(parse_quote!(#this: std::default::Default));
+    }
+    Ok(bounded_where_clause)
+}
+
+// The generics of the input with the `'de` lifetime of `Deserialize<'de>`
+// prepended, outliving every lifetime that fields borrow from the input.
+fn de_generics(input: &DeriveInput) -> Result<Generics> {
+    let borrowed = attr::borrowed_lifetimes(input)?;
+    Ok(bound::with_de_lifetime(&input.generics, &borrowed))
+}
+
+// The function giving the visitor of a field in place of its `Deserialize`
+// impl: the `deserialize_with` hook, or for a `Cow<str>` marked
+// `#[serde(borrow)]` the place that keeps borrowed strings.
+fn field_hook(field: &Field, opts: &FieldOpts) -> Result<Option<TokenStream>> {
+    if let Some(path) = attr::deserialize_hook(&opts.with)? {
+        return Ok(Some(path.into_token_stream()));
+    }
+    if opts.borrow.on && attr::is_cow_str(&field.ty) {
+        return Ok(Some(quote!(__qser::__private::borrow_cow_str)));
+    }
+    Ok(None)
+}
+
+// Implements `Deserialize` for the input with `body`, which returns the
+// visitor for `__out`. A remote mirror gets an inherent `deserialize` function
+// of the same shape instead, to be used through a `with` hook on the remote
+// type.
+fn deserialize_impl(
+    input: &DeriveInput,
+    where_clause: &WhereClause,
+    body: TokenStream,
+) -> Result<TokenStream> {
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, input_where_clause) = input.generics.split_for_impl();
+    let de_generics = de_generics(input)?;
+    let (de_impl_generics, _, _) = de_generics.split_for_impl();
+    let container = attr::attr_container_opts(input)?;
+
+    if container.remote.is_some() {
+        let (this, _) = attr::this_type(input)?;
+        let pretend = pretend::pretend_used(input);
+        let de_lifetime = &de_generics.params[0];
+        return Ok(quote! {
+            #pretend
+
+            impl #impl_generics #ident #ty_generics #input_where_clause {
+                pub fn deserialize<'__a, #de_lifetime>(
+                    __out: &'__a mut std::option::Option<#this>,
+                ) -> &'__a mut dyn __qser::de::Visitor<'de> #where_clause {
+                    #body
                 }
             }
+        });
+    }
 
-            struct __State #wrapper_impl_generics #where_clause {
+    Ok(quote! {
+        impl #de_impl_generics __qser::Deserialize<'de> for #ident #ty_generics #where_clause {
+            fn begin(__out: &mut std::option::Option<Self>) -> &mut dyn __qser::de::Visitor<'de> {
+                #body
+            }
+        }
+    })
+}
+
+// The output type of a struct and the path its fields are constructed with.
+// Remote structs with getters have private fields, so they are constructed
+// as the mirror and converted with `From`.
+fn struct_construct<'a>(
+    input: &DeriveInput,
+    fields: impl IntoIterator<Item = &'a Field>,
+) -> Result<(TokenStream, TokenStream)> {
+    let ident = &input.ident;
+    let (this, this_path) = attr::this_type(input)?;
+    if attr::has_getter(fields)? {
+        Ok((this, quote!(#ident)))
+    } else {
+        Ok((this, this_path))
+    }
+}
+
+// Generates a `de::Map` named `state` which writes `construct { fields }` to
+// an output place of the input type, and an expression that constructs it
+// from `__out: &mut Option<Input>`. The constructed value is converted with
+// `From` if the fields have getters. A key given more than once is an error,
+// rather than letting one of the values silently win.
+fn map_builder(
+    input: &DeriveInput,
+    state: &Ident,
+    construct: &TokenStream,
+    fields: &FieldsNamed,
+    rename_all: Option<&Case>,
+    deny_unknown_fields: bool,
+    default: &DefaultValue,
+) -> Result<(TokenStream, TokenStream)> {
+    let (this, _) = attr::this_type(input)?;
+    let (_, _, where_clause) = input.generics.split_for_impl();
+
+    let mut fieldstr = Vec::new();
+    let mut fieldindex = Vec::new();
+    let mut fieldplace = Vec::new();
+    let mut statename = Vec::new();
+    let mut statety = Vec::new();
+    let mut stateinit = Vec::new();
+    let mut flatten = None;
+    let mut allname = Vec::new();
+    let mut allvalue = Vec::new();
+    for field in &fields.named {
+        let opts = attr::attr_field_opts(field)?;
+        let name = &field.ident;
+        let default = field_default(&opts, default, quote!(#name))?;
+        allname.push(name);
+
+        if opts.skip.deserializing {
+            allvalue.push(default.unwrap_or_else(|| quote!(std::default::Default::default())));
+            continue;
+        }
+
+        let ty = &field.ty;
+        statename.push(name);
+
+        if opts.flatten {
+            if deny_unknown_fields {
+                return Err(Error::new_spanned(
+                    field,
+                    "flatten cannot be used together with deny_unknown_fields",
+                ));
+            }
+            flatten = Some(name);
+            statety.push(quote!(__qser::__private::FlatBuilder<'de, #ty>));
+            stateinit.push(quote!(__qser::__private::FlatBuilder::new()));
+            allvalue.push(quote!(self.#name.finish()?));
+            continue;
+        }
+
+        let key = attr::name_of_field(field, opts.rename.deserialize.as_ref(), rename_all);
+        let aliases = &opts.aliases;
+        fieldindex.push(fieldstr.len());
+        fieldstr.push(quote!(#key #(| #aliases)*));
+        statety.push(quote!(std::option::Option<#ty>));
+        match field_hook(field, &opts)? {
+            Some(path) => {
+                fieldplace.push(quote!(#path(&mut self.#name)));
+                stateinit.push(quote!(std::option::Option::None));
+            }
+            None => {
+                fieldplace.push(quote!(__qser::Deserialize::begin(&mut self.#name)));
+                // A missing field falls back to the field or container
+                // default, so the state must not start out as the type's
+                // own default (`Some(None)` for an Option).
+                stateinit.push(if default.is_some() {
+                    quote!(std::option::Option::None)
+                } else {
+                    quote!(__qser::Deserialize::default())
+                });
+            }
+        }
+        allvalue.push(match default {
+            Some(default) => quote! {
+                match self.#name.take() {
+                    std::option::Option::Some(__v) => __v,
+                    std::option::Option::None => #default,
+                }
+            },
+            None => quote!(self.#name.take().ok_or(__qser::Error)?),
+        });
+    }
+
+    let unknown_field = if let Some(flatten) = flatten {
+        quote!(self.#flatten.key(__k))
+    } else if deny_unknown_fields {
+        quote!(std::result::Result::Err(__qser::Error))
+    } else {
+        quote!(std::result::Result::Ok(<dyn __qser::de::Visitor>::ignore()))
+    };
+    let container_default = container_default(&this, default)?;
+
+    let de_generics = de_generics(input)?;
+    let wrapper_generics = bound::with_lifetime_bound(&de_generics, "'__a");
+    let (wrapper_impl_generics, wrapper_ty_generics, _) = wrapper_generics.split_for_impl();
+    let bounded_where_clause = de_where_clause_with_default(input, default)?;
+    let fieldcount = fieldstr.len();
+
+    let value = quote! {
+        #construct {
+            #(
+                #allname,
+            )*
+        }
+    };
+    let value = if attr::has_getter(&fields.named)? {
+        quote!(std::convert::From::from(#value))
+    } else {
+        value
+    };
+
+    let init = quote! {
+        #state {
+            #(
+                #statename: #stateinit,
+            )*
+            __seen: [false; #fieldcount],
+            __out,
+            __marker: std::marker::PhantomData,
+        }
+    };
+    let items = quote! {
+        struct #state #wrapper_impl_generics #where_clause {
+            #(
+                #statename: #statety,
+            )*
+            __seen: [bool; #fieldcount],
+            __out: &'__a mut std::option::Option<#this>,
+            __marker: std::marker::PhantomData<&'de ()>,
+        }
+
+        impl #wrapper_impl_generics __qser::de::Map<'de> for #state #wrapper_ty_generics #bounded_where_clause {
+            fn key(&mut self, __k: &str) -> __qser::Result<&mut dyn __qser::de::Visitor<'de>> {
+                match __k {
+                    #(
+                        #fieldstr => {
+                            if std::mem::replace(&mut self.__seen[#fieldindex], true) {
+                                return std::result::Result::Err(__qser::Error);
+                            }
+                            std::result::Result::Ok(#fieldplace)
+                        }
+                    )*
+                    _ => #unknown_field,
+                }
+            }
+
+            fn finish(&mut self) -> __qser::Result<()> {
+                #container_default
                 #(
-                    #fieldname: std::option::Option<#fieldty>,
+                    let #allname = #allvalue;
                 )*
-                __out: &'__a mut std::option::Option<#ident #ty_generics>,
+                *self.__out = std::option::Option::Some(#value);
+                std::result::Result::Ok(())
             }
+        }
+    };
+    Ok((init, items))
+}
 
-            impl #wrapper_impl_generics qser::de::Map for __State #wrapper_ty_generics #bounded_where_clause {
-                fn key(&mut self, __k: &str) -> qser::Result<&mut dyn qser::de::Visitor> {
-                    match __k {
-                        #(
-                            #fieldstr => std::result::Result::Ok(qser::Deserialize::begin(&mut self.#fieldname)),
-                        )*
-                        _ => std::result::Result::Ok(<dyn qser::de::Visitor>::ignore()),
-                    }
+// The value of a field that is missing from the input, given by its own
+// default or else by the member of the container default `__default`.
+fn field_default(
+    opts: &FieldOpts,
+    container: &DefaultValue,
+    member: TokenStream,
+) -> Result<Option<TokenStream>> {
+    Ok(if opts.default.on {
+        match &opts.default.path {
+            Some(path) => {
+                let path = attr::parse_path(path)?;
+                Some(quote!(#path()))
+            }
+            None => Some(quote!(std::default::Default::default())),
+        }
+    } else if container.on {
+        Some(quote!(__default.#member))
+    } else {
+        None
+    })
+}
+
+// Binds `__default` for `field_default`, if the container has a default.
+fn container_default(this: &TokenStream, default: &DefaultValue) -> Result<Option<TokenStream>> {
+    if !default.on {
+        return Ok(None);
+    }
+    let init = match &default.path {
+        Some(path) => {
+            let path = attr::parse_path(path)?;
+            quote!(#path())
+        }
+        None => quote!(std::default::Default::default()),
+    };
+    Ok(Some(quote! {
+        let __default: #this = #init;
+    }))
+}
+
+// Same as `map_builder` but generates a `de::Seq` over unnamed fields, which
+// expects as many elements as there are deserialized fields. Trailing
+// elements may be missing if their fields have a default.
+fn seq_builder(
+    input: &DeriveInput,
+    state: &Ident,
+    construct: &TokenStream,
+    fields: &FieldsUnnamed,
+    default: &DefaultValue,
+) -> Result<(TokenStream, TokenStream)> {
+    let (this, _) = attr::this_type(input)?;
+    let (_, _, where_clause) = input.generics.split_for_impl();
+
+    let mut elementplace = Vec::new();
+    let mut statename = Vec::new();
+    let mut statety = Vec::new();
+    let mut allmember = Vec::new();
+    let mut allvalue = Vec::new();
+    for (i, field) in fields.unnamed.iter().enumerate() {
+        let opts = attr::attr_field_opts(field)?;
+        let member = Index::from(i);
+        let default = field_default(&opts, default, quote!(#member))?;
+        allmember.push(member);
+
+        if opts.skip.deserializing {
+            allvalue.push(default.unwrap_or_else(|| quote!(std::default::Default::default())));
+            continue;
+        }
+
+        let name = Ident::new(&format!("__f{}", i), Span::call_site());
+        let ty = &field.ty;
+        elementplace.push(match field_hook(field, &opts)? {
+            Some(path) => quote!(#path(&mut self.#name)),
+            None => quote!(__qser::Deserialize::begin(&mut self.#name)),
+        });
+        statety.push(quote!(std::option::Option<#ty>));
+        allvalue.push(match default {
+            Some(default) => quote! {
+                match self.#name.take() {
+                    std::option::Option::Some(__v) => __v,
+                    std::option::Option::None => #default,
                 }
+            },
+            None => quote!(self.#name.take().ok_or(__qser::Error)?),
+        });
+        statename.push(name);
+    }
+    let index = 0usize..;
+    let container_default = container_default(&this, default)?;
 
-                fn finish(&mut self) -> qser::Result<()> {
+    let de_generics = de_generics(input)?;
+    let wrapper_generics = bound::with_lifetime_bound(&de_generics, "'__a");
+    let (wrapper_impl_generics, wrapper_ty_generics, _) = wrapper_generics.split_for_impl();
+    let bounded_where_clause = de_where_clause_with_default(input, default)?;
+
+    let value = quote! {
+        #construct {
+            #(
+                #allmember: #allvalue,
+            )*
+        }
+    };
+    let value = if attr::has_getter(&fields.unnamed)? {
+        quote!(std::convert::From::from(#value))
+    } else {
+        value
+    };
+
+    let init = quote! {
+        #state {
+            #(
+                #statename: std::option::Option::None,
+            )*
+            __index: 0,
+            __out,
+            __marker: std::marker::PhantomData,
+        }
+    };
+    let items = quote! {
+        struct #state #wrapper_impl_generics #where_clause {
+            #(
+                #statename: #statety,
+            )*
+            __index: usize,
+            __out: &'__a mut std::option::Option<#this>,
+            __marker: std::marker::PhantomData<&'de ()>,
+        }
+
+        impl #wrapper_impl_generics __qser::de::Seq<'de> for #state #wrapper_ty_generics #bounded_where_clause {
+            fn element(&mut self) -> __qser::Result<&mut dyn __qser::de::Visitor<'de>> {
+                let __index = self.__index;
+                self.__index = __index + 1;
+                match __index {
                     #(
-                        let #fieldname = self.#fieldname.take().ok_or(qser::Error)?;
+                        #index => std::result::Result::Ok(#elementplace),
                     )*
-                    *self.__out = std::option::Option::Some(#ident {
-                        #(
-                            #fieldname,
-                        )*
-                    });
+                    _ => std::result::Result::Err(__qser::Error),
+                }
+            }
+
+            fn finish(&mut self) -> __qser::Result<()> {
+                #container_default
+                *self.__out = std::option::Option::Some(#value);
+                std::result::Result::Ok(())
+            }
+        }
+    };
+    Ok((init, items))
+}
+
+pub fn derive_tuple_struct(
+    input: &DeriveInput,
+    container: &ContainerOpts,
+    fields: &FieldsUnnamed,
+) -> Result<TokenStream> {
+    let ident = &input.ident;
+    let (_, _, where_clause) = input.generics.split_for_impl();
+    let de_generics = de_generics(input)?;
+    let (de_impl_generics, de_ty_generics, _) = de_generics.split_for_impl();
+    let dummy = Ident::new(
+        &format!("_IMPL_MINIDESERIALIZE_FOR_{}", ident),
+        Span::call_site(),
+    );
+    let krate = attr::crate_path(input)?;
+
+    let (this, construct) = struct_construct(input, fields.unnamed.iter())?;
+
+    let state = Ident::new("__State", Span::call_site());
+    let (init, items) = seq_builder(input, &state, &construct, fields, &container.default)?;
+
+    let bounded_where_clause = de_where_clause_with_default(input, &container.default)?;
+    let visitor = Ident::new("__Visitor", Span::call_site());
+    let deserialize = deserialize_impl(input, &bounded_where_clause, cast_place(input, &visitor)?)?;
+
+    Ok(quote! {
+        #[allow(non_upper_case_globals)]
+        #[allow(non_local_definitions)]
+        const #dummy: () = {
+            use #krate as __qser;
+
+            #[repr(C)]
+            struct #visitor #de_impl_generics #where_clause {
+                __out: std::option::Option<#this>,
+                __marker: std::marker::PhantomData<&'de ()>,
+            }
+
+            #deserialize
+
+            impl #de_impl_generics __qser::de::Visitor<'de> for #visitor #de_ty_generics #bounded_where_clause {
+                fn seq(&mut self) -> __qser::Result<std::boxed::Box<dyn __qser::de::Seq<'de> + '_>> {
+                    let __out = &mut self.__out;
+                    Ok(std::boxed::Box::new(#init))
+                }
+            }
+
+            #items
+        };
+    })
+}
+
+pub fn derive_unit_struct(input: &DeriveInput) -> Result<TokenStream> {
+    let ident = &input.ident;
+    let (_, _, where_clause) = input.generics.split_for_impl();
+    let de_generics = de_generics(input)?;
+    let (de_impl_generics, de_ty_generics, _) = de_generics.split_for_impl();
+    let dummy = Ident::new(
+        &format!("_IMPL_MINIDESERIALIZE_FOR_{}", ident),
+        Span::call_site(),
+    );
+    let krate = attr::crate_path(input)?;
+    let (this, this_path) = attr::this_type(input)?;
+
+    let bounded_where_clause = de_where_clause(input)?;
+    let visitor = Ident::new("__Visitor", Span::call_site());
+    let deserialize = deserialize_impl(input, &bounded_where_clause, cast_place(input, &visitor)?)?;
+
+    Ok(quote! {
+        #[allow(non_upper_case_globals)]
+        #[allow(non_local_definitions)]
+        const #dummy: () = {
+            use #krate as __qser;
+
+            #[repr(C)]
+            struct #visitor #de_impl_generics #where_clause {
+                __out: std::option::Option<#this>,
+                __marker: std::marker::PhantomData<&'de ()>,
+            }
+
+            #deserialize
+
+            impl #de_impl_generics __qser::de::Visitor<'de> for #visitor #de_ty_generics #bounded_where_clause {
+                fn null(&mut self) -> __qser::Result<()> {
+                    self.__out = std::option::Option::Some(#this_path);
                     std::result::Result::Ok(())
                 }
             }
@@ -110,69 +609,639 @@ pub fn derive_struct(input: &DeriveInput, fields: &FieldsNamed) -> Result<TokenS
     })
 }
 
-pub fn derive_enum(input: &DeriveInput, enumeration: &DataEnum) -> Result<TokenStream> {
-    if input.generics.lt_token.is_some() || input.generics.where_clause.is_some() {
+pub fn derive_transparent(input: &DeriveInput, fields: &Fields) -> Result<TokenStream> {
+    let (_, construct) = struct_construct(input, fields)?;
+
+    let mut inner = None;
+    let mut member = Vec::new();
+    let mut value = Vec::new();
+    for (i, field) in fields.iter().enumerate() {
+        let opts = attr::attr_field_opts(field)?;
+        member.push(attr::member_of_field(i, field));
+        if opts.skip.deserializing {
+            value.push(match &opts.default.path {
+                Some(path) => {
+                    let path = attr::parse_path(path)?;
+                    quote!(#path())
+                }
+                None => quote!(std::default::Default::default()),
+            });
+            continue;
+        }
+        if inner.is_some() {
+            return Err(Error::new_spanned(
+                field,
+                "transparent struct must have exactly one deserialized field",
+            ));
+        }
+        inner = Some(&field.ty);
+        value.push(quote!(__from));
+    }
+    let Some(inner) = inner else {
         return Err(Error::new(
             Span::call_site(),
-            "Enums with generics are not supported",
+            "transparent struct must have exactly one deserialized field",
         ));
+    };
+
+    let value = quote! {
+        #construct {
+            #(
+                #member: #value,
+            )*
+        }
+    };
+    let value = if attr::has_getter(fields)? {
+        quote!(std::convert::From::from(#value))
+    } else {
+        value
+    };
+    derive_convert(input, inner, quote!(std::result::Result::Ok(#value)))
+}
+
+pub fn derive_from(input: &DeriveInput, container: &ContainerOpts) -> Result<TokenStream> {
+    let ident = &input.ident;
+    let (_, ty_generics, _) = input.generics.split_for_impl();
+
+    if let Some(from) = &container.from {
+        let from = attr::parse_type(from)?;
+        let convert = quote! {
+            std::result::Result::Ok(<#ident #ty_generics as std::convert::From<#from>>::from(__from))
+        };
+        derive_convert(input, &from, convert)
+    } else {
+        let from = attr::parse_type(container.try_from.as_ref().unwrap())?;
+        let convert = quote! {
+            <#ident #ty_generics as std::convert::TryFrom<#from>>::try_from(__from)
+                .map_err(|_| __qser::Error)
+        };
+        derive_convert(input, &from, convert)
     }
+}
 
+// Deserializes through an intermediate value of type `from`, using `convert`
+// to turn `__from` into the output.
+fn derive_convert(input: &DeriveInput, from: &Type, convert: TokenStream) -> Result<TokenStream> {
     let ident = &input.ident;
+    let (_, ty_generics, _) = input.generics.split_for_impl();
     let dummy = Ident::new(
         &format!("_IMPL_MINIDESERIALIZE_FOR_{}", ident),
         Span::call_site(),
     );
+    let krate = attr::crate_path(input)?;
 
-    let var_idents = enumeration
-        .variants
-        .iter()
-        .map(|variant| match variant.fields {
-            Fields::Unit => Ok(&variant.ident),
-            _ => Err(Error::new_spanned(
-                variant,
-                "Invalid variant: only simple enum variants without fields are supported",
-            )),
-        })
-        .collect::<Result<Vec<_>>>()?;
-    let names = enumeration
-        .variants
-        .iter()
-        .map(|variant| {
-            let opts = attr::attr_variant_opts(variant)?;
-            let name = attr::name_of_variant(variant, &opts);
-            Ok(name)
-        })
-        .collect::<Result<Vec<_>>>()?;
+    let converter = Ident::new("__Convert", Span::call_site());
+    let begin = quote!(__qser::Deserialize::begin(__from));
+    let items = convert_impl(input, &converter, from, begin, convert)?;
+
+    let bounded_where_clause = de_where_clause(input)?;
+    let deserialize = deserialize_impl(
+        input,
+        &bounded_where_clause,
+        quote!(__qser::__private::ConvertPlace::<#converter #ty_generics>::new(__out)),
+    )?;
 
     Ok(quote! {
         #[allow(non_upper_case_globals)]
+        #[allow(non_local_definitions)]
         const #dummy: () = {
+            use #krate as __qser;
+
+            #items
+
+            #deserialize
+        };
+    })
+}
+
+// Generates a `Convert` impl named `converter` producing the input type from
+// `from`, which is deserialized into `__from` by `begin`.
+fn convert_impl(
+    input: &DeriveInput,
+    converter: &Ident,
+    from: &Type,
+    begin: TokenStream,
+    convert: TokenStream,
+) -> Result<TokenStream> {
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let (this, _) = attr::this_type(input)?;
+
+    let bounded_where_clause = de_where_clause(input)?;
+    let de_generics = de_generics(input)?;
+    let (de_impl_generics, _, _) = de_generics.split_for_impl();
+
+    Ok(quote! {
+        struct #converter #impl_generics (std::marker::PhantomData<#ident #ty_generics>) #where_clause;
+
+        impl #de_impl_generics __qser::__private::Convert<'de> for #converter #ty_generics #bounded_where_clause {
+            type From = #from;
+            type Into = #this;
+
+            fn begin(__from: &mut std::option::Option<Self::From>) -> &mut dyn __qser::de::Visitor<'de> {
+                #begin
+            }
+
+            fn convert(__from: #from) -> __qser::Result<Self::Into> {
+                #convert
+            }
+        }
+    })
+}
+
+// Reinterprets `__out: &mut Option<Input>` as the `#[repr(C)]` place `place`.
+// Places hold a `PhantomData<&'de ()>` after the output, so that borrowing a
+// place also implies that `'de` outlives the borrow, as the boxed builders
+// returned from `seq` and `map` require.
+fn cast_place(input: &DeriveInput, place: &Ident) -> Result<TokenStream> {
+    let de_generics = de_generics(input)?;
+    let (_, ty_generics, _) = de_generics.split_for_impl();
+    let (this, _) = attr::this_type(input)?;
+
+    Ok(quote! {
+        unsafe {
+            &mut *{
+                __out
+                as *mut std::option::Option<#this>
+                as *mut #place #ty_generics
+            }
+        }
+    })
+}
+
+pub fn derive_enum(
+    input: &DeriveInput,
+    container: &ContainerOpts,
+    enumeration: &DataEnum,
+) -> Result<TokenStream> {
+    let ident = &input.ident;
+    let (_, ty_generics, where_clause) = input.generics.split_for_impl();
+    let de_generics = de_generics(input)?;
+    let (de_impl_generics, de_ty_generics, _) = de_generics.split_for_impl();
+    let dummy = Ident::new(
+        &format!("_IMPL_MINIDESERIALIZE_FOR_{}", ident),
+        Span::call_site(),
+    );
+    let krate = attr::crate_path(input)?;
+    let (this, this_path) = attr::this_type(input)?;
+
+    let wrapper_generics = bound::with_lifetime_bound(&de_generics, "'__a");
+    let (wrapper_impl_generics, wrapper_ty_generics, _) = wrapper_generics.split_for_impl();
+    let bounded_where_clause = de_where_clause(input)?;
+
+    // Every variant gets a place for its payload, which writes the variant
+    // to the output once the payload is complete.
+    let mut name = Vec::new();
+    let mut place = Vec::new();
+    let mut unit_name = Vec::new();
+    let mut unit_ident = Vec::new();
+    let mut other = None;
+    let mut items = Vec::new();
+    for (i, variant) in enumeration.variants.iter().enumerate() {
+        let opts = attr::attr_variant_opts(variant)?;
+        if opts.skip.deserializing {
+            continue;
+        }
+        let var_ident = &variant.ident;
+        let key = attr::name_of_variant(
+            variant,
+            opts.rename.deserialize.as_ref(),
+            container.rename_all.deserialize.as_ref(),
+        );
+        let variant_place = Ident::new(&format!("__Variant{}", i), Span::call_site());
+        if let (TagStyle::Internal { .. }, Fields::Unnamed(fields)) =
+            (&container.tag_style, &variant.fields)
+            && fields.unnamed.len() != 1
+        {
+            return Err(Error::new_spanned(
+                variant,
+                "internally tagged enums cannot have tuple variants",
+            ));
+        }
+
+        if opts.other {
+            if !matches!(variant.fields, Fields::Unit) {
+                return Err(Error::new_spanned(
+                    variant,
+                    "#[serde(other)] must be on a unit variant",
+                ));
+            }
+            if let TagStyle::Untagged = container.tag_style {
+                return Err(Error::new_spanned(
+                    variant,
+                    "#[serde(other)] cannot be used in untagged enums",
+                ));
+            }
+            if other.is_some() {
+                return Err(Error::new_spanned(
+                    variant,
+                    "only one variant can be marked #[serde(other)]",
+                ));
+            }
+            other = Some((var_ident, cast_place(input, &variant_place)?));
+        }
+
+        let visit = match &variant.fields {
+            // The catch-all variant accepts and ignores any payload.
+            Fields::Unit if opts.other => {
+                unit_name.push(key.clone());
+                unit_ident.push(var_ident);
+                let value = quote!(std::option::Option::Some(#this_path::#var_ident));
+                quote! {
+                    fn null(&mut self) -> __qser::Result<()> {
+                        self.__out = #value;
+                        std::result::Result::Ok(())
+                    }
+
+                    fn boolean(&mut self, _b: bool) -> __qser::Result<()> {
+                        self.__out = #value;
+                        std::result::Result::Ok(())
+                    }
+
+                    fn string(&mut self, _s: &str) -> __qser::Result<()> {
+                        self.__out = #value;
+                        std::result::Result::Ok(())
+                    }
+
+                    fn negative(&mut self, _n: i64) -> __qser::Result<()> {
+                        self.__out = #value;
+                        std::result::Result::Ok(())
+                    }
+
+                    fn nonnegative(&mut self, _n: u64) -> __qser::Result<()> {
+                        self.__out = #value;
+                        std::result::Result::Ok(())
+                    }
+
+                    fn float(&mut self, _n: f64) -> __qser::Result<()> {
+                        self.__out = #value;
+                        std::result::Result::Ok(())
+                    }
+
+                    fn seq(&mut self) -> __qser::Result<std::boxed::Box<dyn __qser::de::Seq<'de> + '_>> {
+                        self.__out = #value;
+                        <dyn __qser::de::Visitor>::ignore().seq()
+                    }
+
+                    fn map(&mut self) -> __qser::Result<std::boxed::Box<dyn __qser::de::Map<'de> + '_>> {
+                        self.__out = #value;
+                        <dyn __qser::de::Visitor>::ignore().map()
+                    }
+                }
+            }
+            Fields::Unit => {
+                unit_name.push(key.clone());
+                unit_ident.push(var_ident);
+                // Internally tagged unit variants are a map of just the tag.
+                let map = match &container.tag_style {
+                    TagStyle::Internal { .. } => Some(quote! {
+                        fn map(&mut self) -> __qser::Result<std::boxed::Box<dyn __qser::de::Map<'de> + '_>> {
+                            self.__out = std::option::Option::Some(#this_path::#var_ident);
+                            <dyn __qser::de::Visitor>::ignore().map()
+                        }
+                    }),
+                    _ => None,
+                };
+                quote! {
+                    fn null(&mut self) -> __qser::Result<()> {
+                        self.__out = std::option::Option::Some(#this_path::#var_ident);
+                        std::result::Result::Ok(())
+                    }
+
+                    #map
+                }
+            }
+            Fields::Unnamed(fields) if fields.unnamed.len() == 1 => {
+                let field = &fields.unnamed[0];
+                let opts = attr::attr_field_opts(field)?;
+                let begin = match field_hook(field, &opts)? {
+                    Some(path) => quote!(#path(__from)),
+                    None => quote!(__qser::Deserialize::begin(__from)),
+                };
+                let convert = quote!(std::result::Result::Ok(#this_path::#var_ident(__from)));
+                items.push(convert_impl(
+                    input,
+                    &variant_place,
+                    &field.ty,
+                    begin,
+                    convert,
+                )?);
+                name.push(key);
+                place.push(quote! {
+                    __qser::__private::ConvertPlace::<#variant_place #ty_generics>::new(__out)
+                });
+                continue;
+            }
+            Fields::Unnamed(fields) => {
+                let state = Ident::new(&format!("__Variant{}Seq", i), Span::call_site());
+                let construct = quote!(#this_path::#var_ident);
+                let (init, builder) =
+                    seq_builder(input, &state, &construct, fields, &DefaultValue::default())?;
+                items.push(builder);
+                quote! {
+                    fn seq(&mut self) -> __qser::Result<std::boxed::Box<dyn __qser::de::Seq<'de> + '_>> {
+                        let __out = &mut self.__out;
+                        Ok(std::boxed::Box::new(#init))
+                    }
+                }
+            }
+            Fields::Named(fields) => {
+                let state = Ident::new(&format!("__Variant{}Map", i), Span::call_site());
+                let construct = quote!(#this_path::#var_ident);
+                let rename_all = opts
+                    .rename_all
+                    .deserialize
+                    .as_ref()
+                    .or(container.rename_all_fields.deserialize.as_ref());
+                let (init, builder) = map_builder(
+                    input,
+                    &state,
+                    &construct,
+                    fields,
+                    rename_all,
+                    container.deny_unknown_fields,
+                    &DefaultValue::default(),
+                )?;
+                items.push(builder);
+                quote! {
+                    fn map(&mut self) -> __qser::Result<std::boxed::Box<dyn __qser::de::Map<'de> + '_>> {
+                        let __out = &mut self.__out;
+                        Ok(std::boxed::Box::new(#init))
+                    }
+                }
+            }
+        };
+
+        items.push(quote! {
+            #[repr(C)]
+            struct #variant_place #de_impl_generics #where_clause {
+                __out: std::option::Option<#this>,
+                __marker: std::marker::PhantomData<&'de ()>,
+            }
+
+            impl #de_impl_generics __qser::de::Visitor<'de> for #variant_place #de_ty_generics #bounded_where_clause {
+                #visit
+            }
+        });
+        name.push(key);
+        place.push(cast_place(input, &variant_place)?);
+    }
+
+    // Unrecognized variant names go to the catch-all variant, if any.
+    let (other_value, other_place) = match other {
+        Some((var_ident, place)) => (
+            quote!(#this_path::#var_ident),
+            quote!(std::result::Result::Ok(#place)),
+        ),
+        None => (
+            quote!(return std::result::Result::Err(__qser::Error)),
+            quote!(std::result::Result::Err(__qser::Error)),
+        ),
+    };
+
+    // Plugged into the `Select` of the tagged builders in `__private`.
+    let select_generics = bound::with_lifetime(&de_generics, "'__a");
+    let (select_impl_generics, _, _) = select_generics.split_for_impl();
+    let select = quote! {
+        fn __select #select_impl_generics (
+            __out: &'__a mut std::option::Option<#this>,
+            __variant: &str,
+        ) -> __qser::Result<&'__a mut dyn __qser::de::Visitor<'de>> #bounded_where_clause {
+            match __variant {
+                #(
+                    #name => std::result::Result::Ok(#place),
+                )*
+                _ => #other_place,
+            }
+        }
+    };
+
+    let visitor_ident = Ident::new("__Visitor", Span::call_site());
+    let begin_visitor = deserialize_impl(
+        input,
+        &bounded_where_clause,
+        cast_place(input, &visitor_ident)?,
+    )?;
+    let visitor = |visit: TokenStream| {
+        quote! {
             #[repr(C)]
-            struct __Visitor {
-                __out: std::option::Option<#ident>,
+            struct #visitor_ident #de_impl_generics #where_clause {
+                __out: std::option::Option<#this>,
+                __marker: std::marker::PhantomData<&'de ()>,
             }
 
-            impl qser::Deserialize for #ident {
-                fn begin(__out: &mut std::option::Option<Self>) -> &mut dyn qser::de::Visitor {
-                    unsafe {
-                        &mut *{
-                            __out
-                            as *mut std::option::Option<Self>
-                            as *mut __Visitor
+            #begin_visitor
+
+            impl #de_impl_generics __qser::de::Visitor<'de> for #visitor_ident #de_ty_generics #bounded_where_clause {
+                #visit
+            }
+        }
+    };
+
+    let (deserialize, tagged) = match &container.tag_style {
+        TagStyle::External => {
+            // Unit variants may also be given as just their name.
+            let string = if unit_name.is_empty() {
+                None
+            } else {
+                Some(quote! {
+                    fn string(&mut self, __s: &str) -> __qser::Result<()> {
+                        let __value = match __s {
+                            #(
+                                #unit_name => #this_path::#unit_ident,
+                            )*
+                            _ => #other_value,
+                        };
+                        self.__out = std::option::Option::Some(__value);
+                        std::result::Result::Ok(())
+                    }
+                })
+            };
+            let visit = quote! {
+                #string
+
+                fn map(&mut self) -> __qser::Result<std::boxed::Box<dyn __qser::de::Map<'de> + '_>> {
+                    Ok(std::boxed::Box::new(__Tagged {
+                        __out: &mut self.__out,
+                        __seen: false,
+                        __marker: std::marker::PhantomData,
+                    }))
+                }
+            };
+            let tagged = quote! {
+                // Map of a single entry from variant name to payload.
+                struct __Tagged #wrapper_impl_generics #where_clause {
+                    __out: &'__a mut std::option::Option<#this>,
+                    __seen: bool,
+                    __marker: std::marker::PhantomData<&'de ()>,
+                }
+
+                impl #wrapper_impl_generics __qser::de::Map<'de> for __Tagged #wrapper_ty_generics #bounded_where_clause {
+                    fn key(&mut self, __k: &str) -> __qser::Result<&mut dyn __qser::de::Visitor<'de>> {
+                        if self.__seen {
+                            return std::result::Result::Err(__qser::Error);
+                        }
+                        self.__seen = true;
+                        let __out = &mut *self.__out;
+                        match __k {
+                            #(
+                                #name => std::result::Result::Ok(#place),
+                            )*
+                            _ => #other_place,
+                        }
+                    }
+
+                    fn finish(&mut self) -> __qser::Result<()> {
+                        if self.__out.is_some() {
+                            std::result::Result::Ok(())
+                        } else {
+                            std::result::Result::Err(__qser::Error)
                         }
                     }
                 }
+            };
+            (visitor(visit), tagged)
+        }
+        TagStyle::Internal { field } => {
+            let visit = quote! {
+                fn map(&mut self) -> __qser::Result<std::boxed::Box<dyn __qser::de::Map<'de> + '_>> {
+                    Ok(std::boxed::Box::new(__qser::__private::TaggedBuilder::new(
+                        #field,
+                        &mut self.__out,
+                        __select,
+                    )))
+                }
+            };
+            (visitor(visit), select)
+        }
+        TagStyle::Adjacent { tag, content } => {
+            let deny_unknown_fields = container.deny_unknown_fields;
+            let visit = quote! {
+                fn map(&mut self) -> __qser::Result<std::boxed::Box<dyn __qser::de::Map<'de> + '_>> {
+                    Ok(std::boxed::Box::new(__qser::__private::AdjacentBuilder::new(
+                        #tag,
+                        #content,
+                        #deny_unknown_fields,
+                        &mut self.__out,
+                        __select,
+                    )))
+                }
+            };
+            (visitor(visit), select)
+        }
+        TagStyle::Untagged => {
+            // Captured whole, then tried against each variant in turn.
+            let converter = Ident::new("__Convert", Span::call_site());
+            let from = parse_quote!(__qser::__private::Content<'de>);
+            let begin = quote!(__qser::Deserialize::begin(__from));
+            let convert = quote!(__qser::__private::untagged(&__from, &[#(#name),*], __select));
+            let convert = convert_impl(input, &converter, &from, begin, convert)?;
+            let begin_convert = deserialize_impl(
+                input,
+                &bounded_where_clause,
+                quote!(__qser::__private::ConvertPlace::<#converter #ty_generics>::new(__out)),
+            )?;
+            let deserialize = quote! {
+                #begin_convert
+
+                #convert
+            };
+            (deserialize, select)
+        }
+    };
+
+    Ok(quote! {
+        #[allow(non_upper_case_globals)]
+        #[allow(non_local_definitions)]
+        const #dummy: () = {
+            use #krate as __qser;
+
+            #deserialize
+
+            #tagged
+
+            #(
+                #items
+            )*
+        };
+    })
+}
+
+// Unit variants read from their discriminant, for `#[qser(repr = "int")]`.
+// Unknown discriminants go to the `#[serde(other)]` variant, if any.
+fn derive_enum_int(input: &DeriveInput, enumeration: &DataEnum) -> Result<TokenStream> {
+    let ident = &input.ident;
+    let (_, _, where_clause) = input.generics.split_for_impl();
+    let de_generics = de_generics(input)?;
+    let (de_impl_generics, de_ty_generics, _) = de_generics.split_for_impl();
+    let dummy = Ident::new(
+        &format!("_IMPL_MINIDESERIALIZE_FOR_{}", ident),
+        Span::call_site(),
+    );
+    let krate = attr::crate_path(input)?;
+    let (this, this_path) = attr::this_type(input)?;
+
+    let mut var_ident = Vec::new();
+    let mut other = None;
+    for variant in &enumeration.variants {
+        let opts = attr::attr_variant_opts(variant)?;
+        if opts.skip.deserializing {
+            continue;
+        }
+        if opts.other {
+            if other.is_some() {
+                return Err(Error::new_spanned(
+                    variant,
+                    "only one variant can be marked #[serde(other)]",
+                ));
             }
+            other = Some(&variant.ident);
+        }
+        var_ident.push(&variant.ident);
+    }
+    let other = match other {
+        Some(var_ident) => quote! {
+            self.__out = std::option::Option::Some(#this_path::#var_ident);
+            std::result::Result::Ok(())
+        },
+        None => quote!(std::result::Result::Err(__qser::Error)),
+    };
+    let select = quote! {
+        let __n = __n as i128;
+        #(
+            if __n == #this_path::#var_ident as i128 {
+                self.__out = std::option::Option::Some(#this_path::#var_ident);
+                return std::result::Result::Ok(());
+            }
+        )*
+        #other
+    };
 
-            impl qser::de::Visitor for __Visitor {
-                fn string(&mut self, s: &str) -> qser::Result<()> {
-                    let value = match s {
-                        #( #names => #ident::#var_idents, )*
-                        _ => { return std::option::Result::Err(qser::Error) },
-                    };
-                    self.__out = std::option::Option::Some(value);
-                    std::result::Result::Ok(())
+    let bounded_where_clause = de_where_clause(input)?;
+    let visitor = Ident::new("__Visitor", Span::call_site());
+    let deserialize = deserialize_impl(input, &bounded_where_clause, cast_place(input, &visitor)?)?;
+
+    Ok(quote! {
+        #[allow(non_upper_case_globals)]
+        #[allow(non_local_definitions)]
+        const #dummy: () = {
+            use #krate as __qser;
+
+            #[repr(C)]
+            struct #visitor #de_impl_generics #where_clause {
+                __out: std::option::Option<#this>,
+                __marker: std::marker::PhantomData<&'de ()>,
+            }
+
+            #deserialize
+
+            impl #de_impl_generics __qser::de::Visitor<'de> for #visitor #de_ty_generics #bounded_where_clause {
+                fn negative(&mut self, __n: i64) -> __qser::Result<()> {
+                    #select
+                }
+
+                fn nonnegative(&mut self, __n: u64) -> __qser::Result<()> {
+                    #select
                 }
             }
         };
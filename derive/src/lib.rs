@@ -10,19 +10,20 @@ mod attr;
 mod bound;
 mod de;
 mod opts;
+mod pretend;
 mod ser;
 
 use proc_macro::TokenStream;
 use syn::{DeriveInput, parse_macro_input};
 
-#[proc_macro_derive(Serialize, attributes(serde))]
+#[proc_macro_derive(Serialize, attributes(serde, qser))]
 pub fn derive_serialize(input: TokenStream) -> TokenStream {
     ser::derive(parse_macro_input!(input as DeriveInput))
         .unwrap_or_else(|err| err.to_compile_error())
         .into()
 }
 
-#[proc_macro_derive(Deserialize, attributes(serde))]
+#[proc_macro_derive(Deserialize, attributes(serde, qser))]
 pub fn derive_deserialize(input: TokenStream) -> TokenStream {
     de::derive(parse_macro_input!(input as DeriveInput))
         .unwrap_or_else(|err| err.to_compile_error())
@@ -1,8 +1,8 @@
 use std::default::Default;
-use std::mem;
 use std::str::FromStr;
 
-use anyhow::{Error, Result, anyhow, bail};
+use proc_macro2::Span;
+use syn::{Error, Result};
 
 // https://serde.rs/attributes.html
 pub enum Modifier {
@@ -14,14 +14,14 @@ pub enum Modifier {
 
     // #[serde(rename_all = "...")]
     RenameAll {
-        serialize_case: Option<String>,
-        deserialize_case: Option<String>,
+        serialize_case: Option<Case>,
+        deserialize_case: Option<Case>,
     },
 
     // #[serde(rename_all_fields = "...")]
     RenameAllFields {
-        serialize_case: Option<String>,
-        deserialize_case: Option<String>,
+        serialize_case: Option<Case>,
+        deserialize_case: Option<Case>,
     },
 
     // #[serde(deny_unknown_fields)]
@@ -40,6 +40,11 @@ pub enum Modifier {
     // #[serde(untagged)]
     Untagged,
 
+    // #[qser(repr = "int")]
+    Repr {
+        repr: Repr,
+    },
+
     // #[serde(bound = "T: MyTrait")]
     // #[serde(bound(serialize = "T: MySerTrait"))]
     // #[serde(bound(deserialize = "T: MyDeTrait"))]
@@ -147,8 +152,178 @@ pub enum Modifier {
 }
 
 impl Modifier {
-    fn kind_name(&self) -> String {
-        format!("{:?}", mem::discriminant(self))
+    // The name the modifier is written as inside the attribute.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Modifier::Rename { .. } => "rename",
+            Modifier::RenameAll { .. } => "rename_all",
+            Modifier::RenameAllFields { .. } => "rename_all_fields",
+            Modifier::DenyUnknownFields => "deny_unknown_fields",
+            Modifier::Tag { .. } => "tag",
+            Modifier::Content { .. } => "content",
+            Modifier::Untagged => "untagged",
+            Modifier::Repr { .. } => "repr",
+            Modifier::Bound { .. } => "bound",
+            Modifier::Default { .. } => "default",
+            Modifier::Remote { .. } => "remote",
+            Modifier::Transparent => "transparent",
+            Modifier::From { .. } => "from",
+            Modifier::TryFrom { .. } => "try_from",
+            Modifier::Into { .. } => "into",
+            Modifier::Crate { .. } => "crate",
+            Modifier::Expecting { .. } => "expecting",
+            Modifier::VariantIdentifier => "variant_identifier",
+            Modifier::FieldIdentifier => "field_identifier",
+            Modifier::Alias { .. } => "alias",
+            Modifier::Skip => "skip",
+            Modifier::SkipSerializing => "skip_serializing",
+            Modifier::SkipDeserializing => "skip_deserializing",
+            Modifier::SerializeWith { .. } => "serialize_with",
+            Modifier::DeserializeWith { .. } => "deserialize_with",
+            Modifier::With { .. } => "with",
+            Modifier::Borrow { .. } => "borrow",
+            Modifier::Other => "other",
+            Modifier::Flatten => "flatten",
+            Modifier::SkipSerializingIf { .. } => "skip_serializing_if",
+            Modifier::Getter { .. } => "getter",
+        }
+    }
+
+    // The setting the modifier configures, shared by alternative ways of
+    // writing it, such as the tag styles or the hooks given by `with`.
+    pub fn setting(&self) -> &'static str {
+        match self {
+            Modifier::Tag { .. }
+            | Modifier::Content { .. }
+            | Modifier::Untagged
+            | Modifier::Repr { .. } => "tag",
+            Modifier::From { .. } | Modifier::TryFrom { .. } => "from",
+            Modifier::Skip
+            | Modifier::SkipSerializing
+            | Modifier::SkipDeserializing
+            | Modifier::SkipSerializingIf { .. } => "skip",
+            Modifier::SerializeWith { .. }
+            | Modifier::DeserializeWith { .. }
+            | Modifier::With { .. } => "with",
+            _ => self.name(),
+        }
+    }
+
+    // Whether the setting is configured for serializing and for
+    // deserializing. Settings which do not differ between the two, such as
+    // the tag style, count as both.
+    pub fn sides(&self) -> (bool, bool) {
+        match self {
+            Modifier::Rename {
+                serialize_name,
+                deserialize_name,
+            } => (serialize_name.is_some(), deserialize_name.is_some()),
+            Modifier::RenameAll {
+                serialize_case,
+                deserialize_case,
+            }
+            | Modifier::RenameAllFields {
+                serialize_case,
+                deserialize_case,
+            } => (serialize_case.is_some(), deserialize_case.is_some()),
+            Modifier::Bound {
+                serialize,
+                deserialize,
+            } => (serialize.is_some(), deserialize.is_some()),
+            Modifier::SkipSerializing
+            | Modifier::SkipSerializingIf { .. }
+            | Modifier::SerializeWith { .. } => (true, false),
+            Modifier::SkipDeserializing | Modifier::DeserializeWith { .. } => (false, true),
+            _ => (true, true),
+        }
+    }
+
+    // Keeps only the given sides of a modifier which configures both, e.g.
+    // `with = "m"` keeping the deserializing side is `deserialize_with =
+    // "m::deserialize"`.
+    pub fn restrict(self, serialize: bool, deserialize: bool) -> Modifier {
+        fn keep<T>(side: bool, value: Option<T>) -> Option<T> {
+            if side { value } else { None }
+        }
+
+        match self {
+            Modifier::Rename {
+                serialize_name,
+                deserialize_name,
+            } => Modifier::Rename {
+                serialize_name: keep(serialize, serialize_name),
+                deserialize_name: keep(deserialize, deserialize_name),
+            },
+            Modifier::RenameAll {
+                serialize_case,
+                deserialize_case,
+            } => Modifier::RenameAll {
+                serialize_case: keep(serialize, serialize_case),
+                deserialize_case: keep(deserialize, deserialize_case),
+            },
+            Modifier::RenameAllFields {
+                serialize_case,
+                deserialize_case,
+            } => Modifier::RenameAllFields {
+                serialize_case: keep(serialize, serialize_case),
+                deserialize_case: keep(deserialize, deserialize_case),
+            },
+            Modifier::Bound {
+                serialize: serialize_bound,
+                deserialize: deserialize_bound,
+            } => Modifier::Bound {
+                serialize: keep(serialize, serialize_bound),
+                deserialize: keep(deserialize, deserialize_bound),
+            },
+            Modifier::Skip if serialize => Modifier::SkipSerializing,
+            Modifier::Skip => Modifier::SkipDeserializing,
+            Modifier::With { imp } if serialize => Modifier::SerializeWith {
+                imp: format!("{}::serialize", imp),
+            },
+            Modifier::With { imp } => Modifier::DeserializeWith {
+                imp: format!("{}::deserialize", imp),
+            },
+            modifier => modifier,
+        }
+    }
+}
+
+// A modifier along with the span of the attribute it was written as, so that
+// errors can point at it.
+pub struct SpannedModifier {
+    pub modifier: Modifier,
+    pub span: Span,
+}
+
+// Collects errors so that every invalid attribute is reported at once rather
+// than only the first.
+pub struct Errors {
+    error: Option<Error>,
+}
+
+impl Errors {
+    pub fn new() -> Self {
+        Self { error: None }
+    }
+
+    pub fn push(&mut self, error: Error) {
+        match &mut self.error {
+            Some(existing) => existing.combine(error),
+            None => self.error = Some(error),
+        }
+    }
+
+    pub fn check<T>(&mut self, result: Result<T>) {
+        if let Err(error) = result {
+            self.push(error);
+        }
+    }
+
+    pub fn finish(self) -> Result<()> {
+        match self.error {
+            Some(error) => Err(error),
+            None => Ok(()),
+        }
     }
 }
 
@@ -159,11 +334,12 @@ trait CompositeOpt {
 }
 
 pub trait OptionSet {
-    fn apply_modifiers(&mut self, modifiers: &[Modifier]) -> Result<()>;
+    fn apply_modifiers(&mut self, modifiers: &[SpannedModifier]) -> Result<()>;
 }
 
 // ----------------------------------------------------------
 
+#[derive(Clone, Copy)]
 pub enum Case {
     Lowercase,
     Uppercase,
@@ -176,9 +352,9 @@ pub enum Case {
 }
 
 impl FromStr for Case {
-    type Err = Error;
+    type Err = String;
 
-    fn from_str(s: &str) -> Result<Self> {
+    fn from_str(s: &str) -> std::result::Result<Self, String> {
         match s {
             "lowercase" => Ok(Case::Lowercase),
             "UPPERCASE" => Ok(Case::Uppercase),
@@ -188,7 +364,92 @@ impl FromStr for Case {
             "SCREAMING_SNAKE_CASE" => Ok(Case::ScreamingSnakeCase),
             "kebab-case" => Ok(Case::KebabCase),
             "SCREAMING-KEBAB-CASE" => Ok(Case::ScreamingKebabCase),
-            _ => bail!("invalid case: {}", s),
+            _ => Err(format!("invalid case: {}", s)),
+        }
+    }
+}
+
+// How the variants of a unit-only enum are written: by name, or with
+// `#[qser(repr = "int")]` by discriminant.
+#[derive(Clone, Copy, PartialEq)]
+pub enum Repr {
+    Name,
+    Int,
+}
+
+impl FromStr for Repr {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, String> {
+        match s {
+            "int" => Ok(Repr::Int),
+            _ => Err(format!("invalid repr: {}", s)),
+        }
+    }
+}
+
+fn lowercase_first(s: &str) -> String {
+    let mut chars = s.chars();
+    match chars.next() {
+        Some(first) => first.to_lowercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+impl Case {
+    // Variant names are expected to be written in PascalCase.
+    pub fn apply_to_variant(&self, variant: &str) -> String {
+        match self {
+            Case::PascalCase => variant.to_owned(),
+            Case::Lowercase => variant.to_ascii_lowercase(),
+            Case::Uppercase => variant.to_ascii_uppercase(),
+            Case::CamelCase => lowercase_first(variant),
+            Case::SnakeCase => {
+                let mut snake = String::new();
+                for (i, ch) in variant.char_indices() {
+                    if i > 0 && ch.is_uppercase() {
+                        snake.push('_');
+                    }
+                    snake.push(ch.to_ascii_lowercase());
+                }
+                snake
+            }
+            Case::ScreamingSnakeCase => Case::SnakeCase
+                .apply_to_variant(variant)
+                .to_ascii_uppercase(),
+            Case::KebabCase => Case::SnakeCase.apply_to_variant(variant).replace('_', "-"),
+            Case::ScreamingKebabCase => Case::ScreamingSnakeCase
+                .apply_to_variant(variant)
+                .replace('_', "-"),
+        }
+    }
+
+    // Field names are expected to be written in snake_case.
+    pub fn apply_to_field(&self, field: &str) -> String {
+        match self {
+            Case::Lowercase | Case::SnakeCase => field.to_owned(),
+            Case::Uppercase => field.to_ascii_uppercase(),
+            Case::PascalCase => {
+                let mut pascal = String::new();
+                let mut capitalize = true;
+                for ch in field.chars() {
+                    if ch == '_' {
+                        capitalize = true;
+                    } else if capitalize {
+                        pascal.push(ch.to_ascii_uppercase());
+                        capitalize = false;
+                    } else {
+                        pascal.push(ch);
+                    }
+                }
+                pascal
+            }
+            Case::CamelCase => lowercase_first(&Case::PascalCase.apply_to_field(field)),
+            Case::ScreamingSnakeCase => field.to_ascii_uppercase(),
+            Case::KebabCase => field.replace('_', "-"),
+            Case::ScreamingKebabCase => Case::ScreamingSnakeCase
+                .apply_to_field(field)
+                .replace('_', "-"),
         }
     }
 }
@@ -209,16 +470,6 @@ impl CompositeOpt for TagStyle {
                 };
                 Ok(true)
             }
-            Modifier::Content { content } => {
-                let TagStyle::Internal { field } = self else {
-                    todo!()
-                };
-                *self = TagStyle::Adjacent {
-                    tag: field.clone(),
-                    content: content.clone(),
-                };
-                Ok(true)
-            }
             Modifier::Untagged => {
                 *self = TagStyle::Untagged;
                 Ok(true)
@@ -255,6 +506,26 @@ impl CompositeOpt for DefaultValue {
     }
 }
 
+// Without `lifetimes`, every lifetime of the field's type is borrowed.
+#[derive(Default)]
+pub struct Borrow {
+    pub on: bool,
+    pub lifetimes: Option<String>,
+}
+
+impl CompositeOpt for Borrow {
+    fn try_apply_modifier(&mut self, modifier: &Modifier) -> Result<bool> {
+        match modifier {
+            Modifier::Borrow { li } => {
+                self.on = true;
+                self.lifetimes = li.clone();
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
+    }
+}
+
 pub struct Skip {
     pub serializing: bool,
     pub serializing_if: Option<String>,
@@ -284,7 +555,6 @@ impl CompositeOpt for Skip {
                 Ok(true)
             }
             Modifier::SkipSerializingIf { imp } => {
-                self.serializing = true;
                 self.serializing_if = Some(imp.clone());
                 Ok(true)
             }
@@ -297,6 +567,68 @@ impl CompositeOpt for Skip {
     }
 }
 
+// A setting which may differ between serialization and deserialization, such
+// as `#[serde(rename(serialize = "a", deserialize = "b"))]`.
+pub struct SerDe<T> {
+    pub serialize: Option<T>,
+    pub deserialize: Option<T>,
+}
+
+impl<T> Default for SerDe<T> {
+    fn default() -> Self {
+        Self {
+            serialize: None,
+            deserialize: None,
+        }
+    }
+}
+
+impl<T> SerDe<T> {
+    // Sides which are not given keep their previous value.
+    fn set(&mut self, serialize: Option<T>, deserialize: Option<T>) {
+        if serialize.is_some() {
+            self.serialize = serialize;
+        }
+        if deserialize.is_some() {
+            self.deserialize = deserialize;
+        }
+    }
+}
+
+// Custom where-clause predicates which replace the inferred `T: Serialize`
+// and `T: Deserialize` bounds on type parameters.
+#[derive(Default)]
+pub struct Bound {
+    pub serialize: Option<String>,
+    pub deserialize: Option<String>,
+}
+
+impl CompositeOpt for Bound {
+    fn try_apply_modifier(&mut self, modifier: &Modifier) -> Result<bool> {
+        match modifier {
+            Modifier::Bound {
+                serialize,
+                deserialize,
+            } => {
+                if serialize.is_some() {
+                    self.serialize = serialize.clone();
+                }
+                if deserialize.is_some() {
+                    self.deserialize = deserialize.clone();
+                }
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
+    }
+}
+
+// Hooks have the same shape as the trait methods they stand in for:
+//
+//     fn serialize(value: &T) -> qser::ser::Fragment
+//     fn deserialize<'de>(out: &mut Option<T>) -> &mut dyn qser::de::Visitor<'de>
+//
+// A `with` module is expected to provide both under those names.
 pub struct With {
     pub module: Option<String>,
     pub serialize_fn: Option<String>,
@@ -335,63 +667,109 @@ impl CompositeOpt for With {
 
 // ----------------------------------------------------------
 
-fn bad_modifier(modifier: &Modifier) -> Error {
-    anyhow!("bad modifier: {:?}", modifier.kind_name())
+fn unsupported(modifier: &SpannedModifier, position: &str) -> Error {
+    Error::new(
+        modifier.span,
+        format!(
+            "`{}` is not supported on {}",
+            modifier.modifier.name(),
+            position
+        ),
+    )
+}
+
+// Reports each of the named modifiers, which are valid elsewhere but have no
+// meaning in this position.
+pub fn reject(errors: &mut Errors, modifiers: &[SpannedModifier], names: &[&str], position: &str) {
+    for modifier in modifiers {
+        if names.contains(&modifier.modifier.name()) {
+            errors.push(unsupported(modifier, position));
+        }
+    }
+}
+
+fn find(modifiers: &[SpannedModifier], name: &str) -> Option<Span> {
+    modifiers
+        .iter()
+        .find(|modifier| modifier.modifier.name() == name)
+        .map(|modifier| modifier.span)
+}
+
+// Reports `second` if both modifiers are given.
+fn conflict(errors: &mut Errors, modifiers: &[SpannedModifier], first: &str, second: &str) {
+    if find(modifiers, first).is_some()
+        && let Some(span) = find(modifiers, second)
+    {
+        errors.push(Error::new(
+            span,
+            format!("`{}` cannot be used together with `{}`", second, first),
+        ));
+    }
 }
 
 // ----------------------------------------------------------
 
 pub struct ContainerOpts {
-    pub rename: Option<String>,
-    pub rename_all: Option<Case>,
+    pub rename_all: SerDe<Case>,
+    pub rename_all_fields: SerDe<Case>,
+    pub deny_unknown_fields: bool,
     pub tag_style: TagStyle,
+    pub repr: Repr,
+    pub bound: Bound,
     pub default: DefaultValue,
     pub remote: Option<String>,
     pub transparent: bool,
     pub from: Option<String>,
     pub try_from: Option<String>,
     pub into: Option<String>,
+    pub crate_path: Option<String>,
 }
 
 impl Default for ContainerOpts {
     fn default() -> Self {
         Self {
-            rename: None,
-            rename_all: None,
+            rename_all: SerDe::default(),
+            rename_all_fields: SerDe::default(),
+            deny_unknown_fields: false,
             tag_style: TagStyle::External,
+            repr: Repr::Name,
+            bound: Bound::default(),
             default: DefaultValue::default(),
             remote: None,
             transparent: false,
             from: None,
             try_from: None,
             into: None,
+            crate_path: None,
         }
     }
 }
 
 impl OptionSet for ContainerOpts {
-    fn apply_modifiers(&mut self, modifiers: &[Modifier]) -> Result<()> {
-        for modifier in modifiers {
+    fn apply_modifiers(&mut self, modifiers: &[SpannedModifier]) -> Result<()> {
+        let mut errors = Errors::new();
+        let mut content = None;
+        for spanned in modifiers {
+            let modifier = &spanned.modifier;
             match modifier {
-                Modifier::Rename {
-                    serialize_name,
-                    deserialize_name,
+                Modifier::RenameAll {
+                    serialize_case,
+                    deserialize_case,
                 } => {
-                    assert_eq!(serialize_name, deserialize_name);
-                    if let Some(name) = serialize_name {
-                        self.rename = Some(name.clone());
-                    }
+                    self.rename_all.set(*serialize_case, *deserialize_case);
                 }
-                Modifier::RenameAll {
+                Modifier::RenameAllFields {
                     serialize_case,
                     deserialize_case,
                 } => {
-                    assert_eq!(serialize_case, deserialize_case);
-                    if let Some(case) = serialize_case {
-                        self.rename_all = Some(Case::from_str(case)?);
-                    }
+                    self.rename_all_fields
+                        .set(*serialize_case, *deserialize_case);
                 }
+                Modifier::DenyUnknownFields => self.deny_unknown_fields = true,
+                Modifier::Content { content: name } => content = Some((name.clone(), spanned.span)),
                 _ if self.tag_style.try_apply_modifier(modifier)? => {}
+                Modifier::Repr { repr } => self.repr = *repr,
+                _ if self.bound.try_apply_modifier(modifier)? => {}
                 _ if self.default.try_apply_modifier(modifier)? => {}
                 Modifier::Remote { item } => {
                     self.remote = Some(item.clone());
@@ -406,115 +784,163 @@ impl OptionSet for ContainerOpts {
                 Modifier::Into { item } => {
                     self.into = Some(item.clone());
                 }
-                _ => return Err(bad_modifier(modifier)),
+                Modifier::Crate { path } => {
+                    self.crate_path = Some(path.clone());
+                }
+                _ => errors.push(unsupported(spanned, "containers")),
+            }
+        }
+
+        // The tag may be given after the content, so they are combined last.
+        if let Some((content, span)) = content {
+            match &self.tag_style {
+                TagStyle::Internal { field } => {
+                    self.tag_style = TagStyle::Adjacent {
+                        tag: field.clone(),
+                        content,
+                    };
+                }
+                _ => errors.push(Error::new(
+                    span,
+                    "`content` can only be used together with `tag`",
+                )),
             }
         }
 
-        Ok(())
+        conflict(&mut errors, modifiers, "tag", "untagged");
+        conflict(&mut errors, modifiers, "tag", "repr");
+        conflict(&mut errors, modifiers, "untagged", "repr");
+        conflict(&mut errors, modifiers, "repr", "rename_all");
+        conflict(&mut errors, modifiers, "from", "try_from");
+        conflict(&mut errors, modifiers, "transparent", "rename_all");
+        conflict(&mut errors, modifiers, "transparent", "deny_unknown_fields");
+        conflict(&mut errors, modifiers, "transparent", "default");
+        conflict(&mut errors, modifiers, "remote", "from");
+        conflict(&mut errors, modifiers, "remote", "try_from");
+        conflict(&mut errors, modifiers, "remote", "into");
+
+        errors.finish()
     }
 }
 
 // ----------------------------------------------------------
 
 pub struct VariantOpts {
-    pub rename: Option<String>,
-    pub rename_all: Option<Case>,
+    pub rename: SerDe<String>,
+    pub rename_all: SerDe<Case>,
     pub skip: Skip,
-    pub with: With,
     pub other: bool,
-    pub untagged: bool,
 }
 
 impl Default for VariantOpts {
     fn default() -> Self {
         Self {
-            rename: None,
-            rename_all: None,
+            rename: SerDe::default(),
+            rename_all: SerDe::default(),
             skip: Skip::default(),
-            with: With::default(),
             other: false,
-            untagged: false,
         }
     }
 }
 
 impl OptionSet for VariantOpts {
-    fn apply_modifiers(&mut self, modifiers: &[Modifier]) -> Result<()> {
-        for modifier in modifiers {
+    fn apply_modifiers(&mut self, modifiers: &[SpannedModifier]) -> Result<()> {
+        let mut errors = Errors::new();
+        for spanned in modifiers {
+            let modifier = &spanned.modifier;
             match modifier {
                 Modifier::Rename {
                     serialize_name,
                     deserialize_name,
                 } => {
-                    assert_eq!(serialize_name, deserialize_name);
-                    if let Some(name) = serialize_name {
-                        self.rename = Some(name.clone());
-                    }
+                    self.rename
+                        .set(serialize_name.clone(), deserialize_name.clone());
                 }
                 Modifier::RenameAll {
                     serialize_case,
                     deserialize_case,
                 } => {
-                    assert_eq!(serialize_case, deserialize_case);
-                    if let Some(case) = serialize_case {
-                        self.rename_all = Some(Case::from_str(case)?);
-                    }
+                    self.rename_all.set(*serialize_case, *deserialize_case);
                 }
-                _ if self.skip.try_apply_modifier(modifier)? => {}
-                _ if self.with.try_apply_modifier(modifier)? => {}
+                // Serializing cannot fail, so there is nothing a skipped
+                // variant could serialize as; only deserializing skips.
+                Modifier::SkipDeserializing => self.skip.deserializing = true,
                 Modifier::Other => self.other = true,
-                Modifier::Untagged => self.untagged = true,
-                _ => return Err(bad_modifier(modifier)),
+                _ => errors.push(unsupported(spanned, "enum variants")),
             }
         }
 
-        Ok(())
+        errors.finish()
     }
 }
 
 // ----------------------------------------------------------
 
 pub struct FieldOpts {
-    pub rename: Option<String>,
+    pub rename: SerDe<String>,
+    pub aliases: Vec<String>,
     pub default: DefaultValue,
     pub flatten: bool,
     pub skip: Skip,
     pub with: With,
+    pub borrow: Borrow,
+    pub getter: Option<String>,
 }
 
 impl Default for FieldOpts {
     fn default() -> Self {
         Self {
-            rename: None,
+            rename: SerDe::default(),
+            aliases: Vec::new(),
             default: DefaultValue::default(),
             flatten: false,
             skip: Skip::default(),
             with: With::default(),
+            borrow: Borrow::default(),
+            getter: None,
         }
     }
 }
 
 impl OptionSet for FieldOpts {
-    fn apply_modifiers(&mut self, modifiers: &[Modifier]) -> Result<()> {
-        for modifier in modifiers {
+    fn apply_modifiers(&mut self, modifiers: &[SpannedModifier]) -> Result<()> {
+        let mut errors = Errors::new();
+        for spanned in modifiers {
+            let modifier = &spanned.modifier;
             match modifier {
                 Modifier::Rename {
                     serialize_name,
                     deserialize_name,
                 } => {
-                    assert_eq!(serialize_name, deserialize_name);
-                    if let Some(name) = serialize_name {
-                        self.rename = Some(name.clone());
-                    }
+                    self.rename
+                        .set(serialize_name.clone(), deserialize_name.clone());
                 }
+                Modifier::Alias { name } => self.aliases.push(name.clone()),
                 _ if self.default.try_apply_modifier(modifier)? => {}
                 Modifier::Flatten => self.flatten = true,
                 _ if self.skip.try_apply_modifier(modifier)? => {}
                 _ if self.with.try_apply_modifier(modifier)? => {}
-                _ => return Err(bad_modifier(modifier)),
+                _ if self.borrow.try_apply_modifier(modifier)? => {}
+                Modifier::Getter { item } => {
+                    self.getter = Some(item.clone());
+                }
+                _ => errors.push(unsupported(spanned, "fields")),
             }
         }
 
-        Ok(())
+        // A flattened field has no key of its own and is read and written
+        // through the enclosing map rather than through hooks.
+        for name in [
+            "rename",
+            "alias",
+            "default",
+            "with",
+            "serialize_with",
+            "deserialize_with",
+        ] {
+            conflict(&mut errors, modifiers, "flatten", name);
+        }
+
+        errors.finish()
     }
 }
@@ -0,0 +1,71 @@
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+use syn::{Data, DeriveInput, Fields, Ident, Member};
+
+use crate::attr;
+
+// A remote mirror is never constructed or read by the generated code, which
+// works on the remote type instead. This function does both, on values that
+// never exist, so that the mirror's fields and variants do not trigger dead
+// code warnings.
+pub fn pretend_used(input: &DeriveInput) -> TokenStream {
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let paths = match &input.data {
+        Data::Struct(data) => vec![(quote!(#ident), &data.fields)],
+        Data::Enum(data) => data
+            .variants
+            .iter()
+            .map(|variant| {
+                let var_ident = &variant.ident;
+                (quote!(#ident::#var_ident), &variant.fields)
+            })
+            .collect(),
+        Data::Union(_) => Vec::new(),
+    };
+
+    let mut read = Vec::new();
+    let mut construct = Vec::new();
+    for (path, fields) in paths {
+        let (member, binding) = members(fields);
+        read.push(quote! {
+            std::option::Option::Some(#path { #(#member: #binding,)* }) => {}
+        });
+        construct.push(quote! {
+            match std::option::Option::None {
+                std::option::Option::Some((#(#binding,)*)) => {
+                    let _: #ident #ty_generics = #path { #(#member: #binding,)* };
+                }
+                std::option::Option::None => {}
+            }
+        });
+    }
+
+    quote! {
+        #[allow(dead_code)]
+        fn __pretend_used #impl_generics () #where_clause {
+            match std::option::Option::None::<&#ident #ty_generics> {
+                #(
+                    #read
+                )*
+                _ => {}
+            }
+            #(
+                #construct
+            )*
+        }
+    }
+}
+
+fn members(fields: &Fields) -> (Vec<Member>, Vec<Ident>) {
+    let member = fields
+        .iter()
+        .enumerate()
+        .map(|(i, field)| attr::member_of_field(i, field))
+        .collect();
+    let binding = (0..fields.len())
+        .map(|i| Ident::new(&format!("__f{}", i), Span::call_site()))
+        .collect();
+    (member, binding)
+}
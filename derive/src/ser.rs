@@ -1,136 +1,800 @@
 use proc_macro2::{Span, TokenStream};
 use quote::quote;
 use syn::{
-    Data, DataEnum, DataStruct, DeriveInput, Error, Fields, FieldsNamed, Ident, Result, parse_quote,
+    Data, DataEnum, DataStruct, DeriveInput, Error, ExprPath, Field, Fields, FieldsNamed,
+    FieldsUnnamed, Ident, Index, Member, Result, WhereClause, parse_quote,
 };
 
-use crate::{attr, bound};
+use crate::opts::{Case, ContainerOpts, FieldOpts, Repr, TagStyle};
+use crate::{attr, bound, pretend};
 
 pub fn derive(input: DeriveInput) -> Result<TokenStream> {
+    attr::check_attrs(&input)?;
+    let container = attr::attr_container_opts(&input)?;
+    attr::check_getters(&input, &container)?;
+    attr::check_repr(&input, &container)?;
+    attr::check_internal_tag(&input, &container)?;
     match &input.data {
+        _ if container.into.is_some() => derive_into(&input, &container),
+        Data::Struct(DataStruct { fields, .. })
+            if container.transparent
+                || fields.len() == 1 && matches!(fields, Fields::Unnamed(_)) =>
+        {
+            derive_transparent(&input, fields)
+        }
         Data::Struct(DataStruct {
             fields: Fields::Named(fields),
             ..
-        }) => derive_struct(&input, fields),
-        Data::Enum(enumeration) => derive_enum(&input, enumeration),
-        _ => Err(Error::new(
-            Span::call_site(),
-            "currently only structs with named fields are supported",
-        )),
+        }) => derive_struct(&input, &container, fields),
+        Data::Struct(DataStruct {
+            fields: Fields::Unnamed(fields),
+            ..
+        }) => derive_tuple_struct(&input, fields),
+        Data::Struct(DataStruct {
+            fields: Fields::Unit,
+            ..
+        }) => derive_unit_struct(&input),
+        Data::Enum(enumeration) if container.repr == Repr::Int => {
+            derive_enum_int(&input, enumeration)
+        }
+        Data::Enum(enumeration) => derive_enum(&input, &container, enumeration),
+        Data::Union(_) => Err(Error::new(Span::call_site(), "unions are not supported")),
     }
 }
 
-fn derive_struct(input: &DeriveInput, fields: &FieldsNamed) -> Result<TokenStream> {
+fn derive_struct(
+    input: &DeriveInput,
+    container: &ContainerOpts,
+    fields: &FieldsNamed,
+) -> Result<TokenStream> {
     let ident = &input.ident;
-    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
     let dummy = Ident::new(
         &format!("_IMPL_MINISERIALIZE_FOR_{}", ident),
         Span::call_site(),
     );
+    let krate = attr::crate_path(input)?;
 
-    let fieldname = &fields.named.iter().map(|f| &f.ident).collect::<Vec<_>>();
-    let fieldstr = fields
-        .named
-        .iter()
-        .map(|field| {
-            let opts = attr::attr_field_opts(field)?;
-            let name = attr::name_of_field(field, &opts);
-            Ok(name)
-        })
-        .collect::<Result<Vec<_>>>()?;
-    let index = 0usize..;
+    let stream = Ident::new("__Map", Span::call_site());
+    let (data, _) = attr::this_type(input)?;
+    let (init, items) = map_stream(
+        input,
+        &stream,
+        &data,
+        fields,
+        false,
+        container.rename_all.serialize.as_ref(),
+    )?;
 
-    let wrapper_generics = bound::with_lifetime_bound(&input.generics, "'__a");
-    let (wrapper_impl_generics, wrapper_ty_generics, _) = wrapper_generics.split_for_impl();
-    let bound = parse_quote!(qser::Serialize);
-    let bounded_where_clause = bound::where_clause_with_bound(&input.generics, bound);
+    let bounded_where_clause = ser_where_clause(input)?;
+    let serialize = serialize_impl(
+        input,
+        &bounded_where_clause,
+        quote!(__qser::ser::Fragment::Map(std::boxed::Box::new(#init))),
+    )?;
 
     Ok(quote! {
         #[allow(non_upper_case_globals)]
         #[allow(non_local_definitions)]
         const #dummy: () = {
-            impl #impl_generics qser::Serialize for #ident #ty_generics #bounded_where_clause {
-                fn begin(&self) -> qser::ser::Fragment {
-                    qser::ser::Fragment::Map(std::boxed::Box::new(__Map {
-                        data: self,
-                        state: 0,
-                    }))
+            use #krate as __qser;
+
+            #serialize
+
+            #items
+        };
+    })
+}
+
+// Implements `Serialize` for the input with `body`, which reads the value
+// from `__data`. A remote mirror gets an inherent `serialize` function of the
+// same shape instead, to be used through a `with` hook on the remote type.
+fn serialize_impl(
+    input: &DeriveInput,
+    where_clause: &WhereClause,
+    body: TokenStream,
+) -> Result<TokenStream> {
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, _) = input.generics.split_for_impl();
+    let container = attr::attr_container_opts(input)?;
+
+    if container.remote.is_some() {
+        let (this, _) = attr::this_type(input)?;
+        let pretend = pretend::pretend_used(input);
+        return Ok(quote! {
+            #pretend
+
+            impl #impl_generics #ident #ty_generics #where_clause {
+                pub fn serialize(__data: &#this) -> __qser::ser::Fragment {
+                    #body
                 }
             }
+        });
+    }
+
+    Ok(quote! {
+        impl #impl_generics __qser::Serialize for #ident #ty_generics #where_clause {
+            fn begin(&self) -> __qser::ser::Fragment {
+                let __data = self;
+                #body
+            }
+        }
+    })
+}
 
-            struct __Map #wrapper_impl_generics #where_clause {
-                data: &'__a #ident #ty_generics,
-                state: usize,
+// The where clause of generated impls: `T: Serialize` for every type
+// parameter, unless replaced by `#[serde(bound = "...")]`.
+fn ser_where_clause(input: &DeriveInput) -> Result<WhereClause> {
+    let container = attr::attr_container_opts(input)?;
+    Ok(match &container.bound.serialize {
+        Some(predicates) => bound::where_clause_with_predicates(
+            &input.generics,
+            attr::parse_bound_predicates(predicates)?,
+        ),
+        None => {
+            let bound = parse_quote!(__qser::Serialize);
+            bound::where_clause_with_bound(&input.generics, bound)
+        }
+    })
+}
+
+// The getter through which a field of a remote struct is read. Its value is
+// owned by the stream, so it cannot also be borrowed by `flatten` or `with`.
+fn getter(field: &Field, opts: &FieldOpts) -> Result<Option<ExprPath>> {
+    let Some(path) = &opts.getter else {
+        return Ok(None);
+    };
+    if opts.flatten || attr::serialize_hook(&opts.with)?.is_some() {
+        return Err(Error::new_spanned(
+            field,
+            "`getter` cannot be used together with `flatten` or `with`",
+        ));
+    }
+    attr::parse_path(path).map(Some)
+}
+
+// Generates a `ser::Map` named `stream` over the named fields of `data`, and
+// an expression that constructs it from `__data: &data`. With `by_ref`, the
+// fields of `data` are references to the values being serialized.
+fn map_stream(
+    input: &DeriveInput,
+    stream: &Ident,
+    data: &TokenStream,
+    fields: &FieldsNamed,
+    by_ref: bool,
+    rename_all: Option<&Case>,
+) -> Result<(TokenStream, TokenStream)> {
+    let (_, _, where_clause) = input.generics.split_for_impl();
+
+    let mut entry = Vec::new();
+    let mut flatname = Vec::new();
+    let mut flatinit = Vec::new();
+    let mut withname = Vec::new();
+    let mut withinit = Vec::new();
+    let mut withty = Vec::new();
+    let mut getname = Vec::new();
+    let mut getinit = Vec::new();
+    let mut getty = Vec::new();
+    for field in &fields.named {
+        let opts = attr::attr_field_opts(field)?;
+        if opts.skip.serializing {
+            continue;
+        }
+        let name = &field.ident;
+        let (value, init) = if by_ref {
+            (quote!(self.data.#name), quote!(__data.#name))
+        } else {
+            (quote!(&self.data.#name), quote!(&__data.#name))
+        };
+        let value = match getter(field, &opts)? {
+            Some(path) => {
+                let get = Ident::new(&format!("__get{}", getname.len()), Span::call_site());
+                let value = quote!(&self.#get);
+                getname.push(get);
+                getinit.push(quote!(#path(__data)));
+                getty.push(&field.ty);
+                value
+            }
+            None => value,
+        };
+        let skip = match &opts.skip.serializing_if {
+            Some(path) => {
+                let path = attr::parse_path(path)?;
+                Some(quote! {
+                    if #path(#value) {
+                        continue;
+                    }
+                })
             }
+            None => None,
+        };
+        if opts.flatten {
+            let flat = Ident::new(&format!("__flat{}", flatname.len()), Span::call_site());
+            entry.push(quote! {
+                #skip
+                if self.#flat.has_next() {
+                    self.state = __state;
+                    return self.#flat.take_next();
+                }
+            });
+            flatname.push(flat);
+            flatinit.push(quote!(__qser::__private::FlatMap::new(#init)));
+        } else {
+            let key = attr::name_of_field(field, opts.rename.serialize.as_ref(), rename_all);
+            let value = match attr::serialize_hook(&opts.with)? {
+                Some(path) => {
+                    let with = Ident::new(&format!("__with{}", withname.len()), Span::call_site());
+                    let value = quote!(&self.#with);
+                    withname.push(with);
+                    withinit.push(quote!(__qser::__private::SerializeWith::new(#init, #path)));
+                    withty.push(&field.ty);
+                    value
+                }
+                None => value,
+            };
+            entry.push(quote! {
+                #skip
+                return std::option::Option::Some((
+                    std::borrow::Cow::Borrowed(#key),
+                    #value,
+                ));
+            });
+        }
+    }
+    let index = 0usize..;
 
-            impl #wrapper_impl_generics qser::ser::Map for __Map #wrapper_ty_generics #bounded_where_clause {
-                fn next(&mut self) -> std::option::Option<(std::borrow::Cow<str>, &dyn qser::Serialize)> {
+    let wrapper_generics = bound::with_lifetime_bound(&input.generics, "'__a");
+    let (wrapper_impl_generics, wrapper_ty_generics, _) = wrapper_generics.split_for_impl();
+    let bounded_where_clause = ser_where_clause(input)?;
+
+    let init = quote! {
+        #stream {
+            data: __data,
+            state: 0,
+            #(
+                #flatname: #flatinit,
+            )*
+            #(
+                #withname: #withinit,
+            )*
+            #(
+                #getname: #getinit,
+            )*
+        }
+    };
+    let items = quote! {
+        struct #stream #wrapper_impl_generics #where_clause {
+            data: &'__a #data,
+            state: usize,
+            #(
+                #flatname: __qser::__private::FlatMap<'__a>,
+            )*
+            #(
+                #withname: __qser::__private::SerializeWith<'__a, #withty>,
+            )*
+            #(
+                #getname: #getty,
+            )*
+        }
+
+        impl #wrapper_impl_generics __qser::ser::Map for #stream #wrapper_ty_generics #bounded_where_clause {
+            fn next(&mut self) -> std::option::Option<(std::borrow::Cow<str>, &dyn __qser::Serialize)> {
+                loop {
                     let __state = self.state;
                     self.state = __state + 1;
                     match __state {
                         #(
-                            #index => std::option::Option::Some((
-                                std::borrow::Cow::Borrowed(#fieldstr),
-                                &self.data.#fieldname,
-                            )),
+                            #index => {
+                                #entry
+                            }
                         )*
-                        _ => std::option::Option::None,
+                        _ => return std::option::Option::None,
                     }
                 }
             }
+        }
+    };
+    Ok((init, items))
+}
+
+// Same as `map_stream` but generates a `ser::Seq` over unnamed fields.
+fn seq_stream(
+    input: &DeriveInput,
+    stream: &Ident,
+    data: &TokenStream,
+    fields: &FieldsUnnamed,
+    by_ref: bool,
+) -> Result<(TokenStream, TokenStream)> {
+    let (_, _, where_clause) = input.generics.split_for_impl();
+
+    let mut element = Vec::new();
+    let mut withname = Vec::new();
+    let mut withinit = Vec::new();
+    let mut withty = Vec::new();
+    let mut getname = Vec::new();
+    let mut getinit = Vec::new();
+    let mut getty = Vec::new();
+    for (i, field) in fields.unnamed.iter().enumerate() {
+        let opts = attr::attr_field_opts(field)?;
+        if opts.skip.serializing {
+            continue;
+        }
+        let member = Index::from(i);
+        let (value, init) = if by_ref {
+            (quote!(self.data.#member), quote!(__data.#member))
+        } else {
+            (quote!(&self.data.#member), quote!(&__data.#member))
+        };
+        let value = match getter(field, &opts)? {
+            Some(path) => {
+                let get = Ident::new(&format!("__get{}", getname.len()), Span::call_site());
+                let value = quote!(&self.#get);
+                getname.push(get);
+                getinit.push(quote!(#path(__data)));
+                getty.push(&field.ty);
+                value
+            }
+            None => value,
+        };
+        let skip = match &opts.skip.serializing_if {
+            Some(path) => {
+                let path = attr::parse_path(path)?;
+                Some(quote! {
+                    if #path(#value) {
+                        continue;
+                    }
+                })
+            }
+            None => None,
+        };
+        let value = match attr::serialize_hook(&opts.with)? {
+            Some(path) => {
+                let with = Ident::new(&format!("__with{}", withname.len()), Span::call_site());
+                let value = quote!(&self.#with);
+                withname.push(with);
+                withinit.push(quote!(__qser::__private::SerializeWith::new(#init, #path)));
+                withty.push(&field.ty);
+                value
+            }
+            None => value,
+        };
+        element.push(quote! {
+            #skip
+            return std::option::Option::Some(#value);
+        });
+    }
+    let index = 0usize..;
+
+    let wrapper_generics = bound::with_lifetime_bound(&input.generics, "'__a");
+    let (wrapper_impl_generics, wrapper_ty_generics, _) = wrapper_generics.split_for_impl();
+    let bounded_where_clause = ser_where_clause(input)?;
+
+    let init = quote! {
+        #stream {
+            data: __data,
+            state: 0,
+            #(
+                #withname: #withinit,
+            )*
+            #(
+                #getname: #getinit,
+            )*
+        }
+    };
+    let items = quote! {
+        struct #stream #wrapper_impl_generics #where_clause {
+            data: &'__a #data,
+            state: usize,
+            #(
+                #withname: __qser::__private::SerializeWith<'__a, #withty>,
+            )*
+            #(
+                #getname: #getty,
+            )*
+        }
+
+        impl #wrapper_impl_generics __qser::ser::Seq for #stream #wrapper_ty_generics #bounded_where_clause {
+            fn next(&mut self) -> std::option::Option<&dyn __qser::Serialize> {
+                loop {
+                    let __state = self.state;
+                    self.state = __state + 1;
+                    match __state {
+                        #(
+                            #index => {
+                                #element
+                            }
+                        )*
+                        _ => return std::option::Option::None,
+                    }
+                }
+            }
+        }
+    };
+    Ok((init, items))
+}
+
+fn derive_tuple_struct(input: &DeriveInput, fields: &FieldsUnnamed) -> Result<TokenStream> {
+    let ident = &input.ident;
+    let dummy = Ident::new(
+        &format!("_IMPL_MINISERIALIZE_FOR_{}", ident),
+        Span::call_site(),
+    );
+    let krate = attr::crate_path(input)?;
+
+    let stream = Ident::new("__Seq", Span::call_site());
+    let (data, _) = attr::this_type(input)?;
+    let (init, items) = seq_stream(input, &stream, &data, fields, false)?;
+
+    let bounded_where_clause = ser_where_clause(input)?;
+    let serialize = serialize_impl(
+        input,
+        &bounded_where_clause,
+        quote!(__qser::ser::Fragment::Seq(std::boxed::Box::new(#init))),
+    )?;
+
+    Ok(quote! {
+        #[allow(non_upper_case_globals)]
+        #[allow(non_local_definitions)]
+        const #dummy: () = {
+            use #krate as __qser;
+
+            #serialize
+
+            #items
         };
     })
 }
 
-fn derive_enum(input: &DeriveInput, enumeration: &DataEnum) -> Result<TokenStream> {
-    if input.generics.lt_token.is_some() || input.generics.where_clause.is_some() {
+fn derive_unit_struct(input: &DeriveInput) -> Result<TokenStream> {
+    let ident = &input.ident;
+    let dummy = Ident::new(
+        &format!("_IMPL_MINISERIALIZE_FOR_{}", ident),
+        Span::call_site(),
+    );
+    let krate = attr::crate_path(input)?;
+
+    let bounded_where_clause = ser_where_clause(input)?;
+    let serialize = serialize_impl(
+        input,
+        &bounded_where_clause,
+        quote!(__qser::ser::Fragment::Null),
+    )?;
+
+    Ok(quote! {
+        #[allow(non_upper_case_globals)]
+        #[allow(non_local_definitions)]
+        const #dummy: () = {
+            use #krate as __qser;
+
+            #serialize
+        };
+    })
+}
+
+fn derive_transparent(input: &DeriveInput, fields: &Fields) -> Result<TokenStream> {
+    let ident = &input.ident;
+    let dummy = Ident::new(
+        &format!("_IMPL_MINISERIALIZE_FOR_{}", ident),
+        Span::call_site(),
+    );
+    let krate = attr::crate_path(input)?;
+
+    let mut inner = None;
+    for (i, field) in fields.iter().enumerate() {
+        let opts = attr::attr_field_opts(field)?;
+        if opts.skip.serializing {
+            continue;
+        }
+        if inner.is_some() {
+            return Err(Error::new_spanned(
+                field,
+                "transparent struct must have exactly one serialized field",
+            ));
+        }
+        inner = Some((attr::member_of_field(i, field), opts));
+    }
+    let Some((member, opts)) = inner else {
         return Err(Error::new(
             Span::call_site(),
-            "Enums with generics are not supported",
+            "transparent struct must have exactly one serialized field",
         ));
-    }
+    };
+    let begin = match (&opts.getter, attr::serialize_hook(&opts.with)?) {
+        (Some(path), _) => {
+            let path = attr::parse_path(path)?;
+            quote!(__qser::__private::serialize_owned(#path(__data)))
+        }
+        (None, Some(path)) => quote!(#path(&__data.#member)),
+        (None, None) => quote!(__qser::Serialize::begin(&__data.#member)),
+    };
 
+    let bounded_where_clause = ser_where_clause(input)?;
+    let serialize = serialize_impl(input, &bounded_where_clause, begin)?;
+
+    Ok(quote! {
+        #[allow(non_upper_case_globals)]
+        #[allow(non_local_definitions)]
+        const #dummy: () = {
+            use #krate as __qser;
+
+            #serialize
+        };
+    })
+}
+
+fn derive_into(input: &DeriveInput, container: &ContainerOpts) -> Result<TokenStream> {
     let ident = &input.ident;
+    let (_, ty_generics, _) = input.generics.split_for_impl();
     let dummy = Ident::new(
         &format!("_IMPL_MINISERIALIZE_FOR_{}", ident),
         Span::call_site(),
     );
+    let krate = attr::crate_path(input)?;
 
-    let var_idents = enumeration
-        .variants
-        .iter()
-        .map(|variant| match variant.fields {
-            Fields::Unit => Ok(&variant.ident),
-            _ => Err(Error::new_spanned(
-                variant,
-                "Invalid variant: only simple enum variants without fields are supported",
-            )),
-        })
-        .collect::<Result<Vec<_>>>()?;
-    let names = enumeration
-        .variants
-        .iter()
-        .map(|variant| {
-            let opts = attr::attr_variant_opts(variant)?;
-            let name = attr::name_of_variant(variant, &opts);
-            Ok(name)
-        })
-        .collect::<Result<Vec<_>>>()?;
+    let into = attr::parse_type(container.into.as_ref().unwrap())?;
+    let mut bounded_where_clause = input.generics.clone().make_where_clause().clone();
+    bounded_where_clause.predicates.push(parse_quote! {
+        #ident #ty_generics: std::clone::Clone + std::convert::Into<#into>
+    });
+    bounded_where_clause
+        .predicates
+        .push(parse_quote!(#into: __qser::Serialize));
+    let serialize = serialize_impl(
+        input,
+        &bounded_where_clause,
+        quote! {
+            let __into: #into = std::convert::Into::into(std::clone::Clone::clone(__data));
+            __qser::__private::serialize_owned(__into)
+        },
+    )?;
 
     Ok(quote! {
         #[allow(non_upper_case_globals)]
+        #[allow(non_local_definitions)]
         const #dummy: () = {
-            impl qser::Serialize for #ident {
-                fn begin(&self) -> qser::ser::Fragment {
-                    match self {
-                        #(
-                            #ident::#var_idents => {
-                                qser::ser::Fragment::Str(std::borrow::Cow::Borrowed(#names))
+            use #krate as __qser;
+
+            #serialize
+        };
+    })
+}
+
+fn derive_enum(
+    input: &DeriveInput,
+    container: &ContainerOpts,
+    enumeration: &DataEnum,
+) -> Result<TokenStream> {
+    let ident = &input.ident;
+    let (_, ty_generics, where_clause) = input.generics.split_for_impl();
+    let dummy = Ident::new(
+        &format!("_IMPL_MINISERIALIZE_FOR_{}", ident),
+        Span::call_site(),
+    );
+    let krate = attr::crate_path(input)?;
+    let (_, this) = attr::this_type(input)?;
+
+    let wrapper_generics = bound::with_lifetime_bound(&input.generics, "'__a");
+    let (wrapper_impl_generics, wrapper_ty_generics, _) = wrapper_generics.split_for_impl();
+    let bounded_where_clause = ser_where_clause(input)?;
+
+    let mut arm = Vec::new();
+    let mut items = Vec::new();
+    for (i, variant) in enumeration.variants.iter().enumerate() {
+        let opts = attr::attr_variant_opts(variant)?;
+        let var_ident = &variant.ident;
+        let name = attr::name_of_variant(
+            variant,
+            opts.rename.serialize.as_ref(),
+            container.rename_all.serialize.as_ref(),
+        );
+        if let (TagStyle::Internal { .. }, Fields::Unnamed(fields)) =
+            (&container.tag_style, &variant.fields)
+            && fields.unnamed.len() != 1
+        {
+            return Err(Error::new_spanned(
+                variant,
+                "internally tagged enums cannot have tuple variants",
+            ));
+        }
+
+        // Unit variants have no payload, newtype variants serialize their
+        // field, and the fields of other variants are gathered by reference
+        // into a view which is serialized as a seq or map.
+        let (pattern, payload) = match &variant.fields {
+            Fields::Unit => (quote!(#this::#var_ident), None),
+            Fields::Unnamed(fields) if fields.unnamed.len() == 1 => {
+                let field = &fields.unnamed[0];
+                let opts = attr::attr_field_opts(field)?;
+                let payload = match attr::serialize_hook(&opts.with)? {
+                    Some(path) => quote!(__qser::__private::SerializeWith::new(__f0, #path)),
+                    None => quote!(__f0),
+                };
+                (quote!(#this::#var_ident(__f0)), Some(payload))
+            }
+            fields => {
+                let view = Ident::new(&format!("__Variant{}", i), Span::call_site());
+                let member = fields
+                    .iter()
+                    .enumerate()
+                    .map(|(i, field)| attr::member_of_field(i, field))
+                    .collect::<Vec<_>>();
+                let binding = (0..fields.len())
+                    .map(|i| Ident::new(&format!("__f{}", i), Span::call_site()))
+                    .collect::<Vec<_>>();
+                let ty = fields.iter().map(|field| &field.ty);
+                let data = quote!(#view #wrapper_ty_generics);
+
+                // The marker keeps the view generic over everything the
+                // enum is, whether or not this variant's fields use it.
+                let (marker, decl, (init, stream)) = match fields {
+                    Fields::Named(fields) => {
+                        let marker = Member::Named(Ident::new("__marker", Span::call_site()));
+                        let stream = Ident::new(&format!("__Variant{}Map", i), Span::call_site());
+                        let rename_all = opts
+                            .rename_all
+                            .serialize
+                            .as_ref()
+                            .or(container.rename_all_fields.serialize.as_ref());
+                        let decl = quote! {
+                            struct #view #wrapper_impl_generics #where_clause {
+                                #(
+                                    #member: &'__a #ty,
+                                )*
+                                #marker: std::marker::PhantomData<&'__a #ident #ty_generics>,
                             }
+                        };
+                        let built = map_stream(input, &stream, &data, fields, true, rename_all)?;
+                        (marker, decl, built)
+                    }
+                    Fields::Unnamed(fields) => {
+                        let marker = Member::Unnamed(Index::from(fields.unnamed.len()));
+                        let stream = Ident::new(&format!("__Variant{}Seq", i), Span::call_site());
+                        let decl = quote! {
+                            struct #view #wrapper_impl_generics (
+                                #(
+                                    &'__a #ty,
+                                )*
+                                std::marker::PhantomData<&'__a #ident #ty_generics>,
+                            ) #where_clause;
+                        };
+                        let built = seq_stream(input, &stream, &data, fields, true)?;
+                        (marker, decl, built)
+                    }
+                    Fields::Unit => unreachable!(),
+                };
+                let fragment = match fields {
+                    Fields::Named(_) => quote!(Map),
+                    _ => quote!(Seq),
+                };
+
+                items.push(quote! {
+                    #decl
+
+                    impl #wrapper_impl_generics __qser::Serialize for #view #wrapper_ty_generics #bounded_where_clause {
+                        fn begin(&self) -> __qser::ser::Fragment {
+                            let __data = self;
+                            __qser::ser::Fragment::#fragment(std::boxed::Box::new(#init))
+                        }
+                    }
+
+                    #stream
+                });
+
+                let pattern = quote!(#this::#var_ident { #(#member: #binding,)* });
+                let payload = quote! {
+                    #view {
+                        #(
+                            #member: #binding,
                         )*
+                        #marker: std::marker::PhantomData::<&#ident #ty_generics>,
                     }
+                };
+                (pattern, Some(payload))
+            }
+        };
+
+        arm.push(match (&container.tag_style, payload) {
+            (TagStyle::External, None) => quote! {
+                #pattern => __qser::ser::Fragment::Str(std::borrow::Cow::Borrowed(#name))
+            },
+            (TagStyle::External, Some(payload)) => quote! {
+                #pattern => __qser::ser::Fragment::Map(std::boxed::Box::new(
+                    __qser::__private::Entry::new(#name, #payload),
+                ))
+            },
+            (TagStyle::Internal { field }, payload) => {
+                let content = match payload {
+                    Some(payload) => quote!(__qser::__private::serialize_owned(#payload)),
+                    None => quote!(__qser::ser::Fragment::Null),
+                };
+                quote! {
+                    #pattern => __qser::ser::Fragment::Map(std::boxed::Box::new(
+                        __qser::__private::TaggedMap::new(#field, #name, #content),
+                    ))
                 }
             }
+            (TagStyle::Adjacent { tag, .. }, None) => quote! {
+                #pattern => __qser::ser::Fragment::Map(std::boxed::Box::new(
+                    __qser::__private::TaggedMap::new(#tag, #name, __qser::ser::Fragment::Null),
+                ))
+            },
+            (TagStyle::Adjacent { tag, content }, Some(payload)) => quote! {
+                #pattern => __qser::ser::Fragment::Map(std::boxed::Box::new(
+                    __qser::__private::TaggedMap::new(
+                        #tag,
+                        #name,
+                        __qser::ser::Fragment::Map(std::boxed::Box::new(
+                            __qser::__private::Entry::new(#content, #payload),
+                        )),
+                    ),
+                ))
+            },
+            (TagStyle::Untagged, None) => quote! {
+                #pattern => __qser::ser::Fragment::Null
+            },
+            (TagStyle::Untagged, Some(payload)) => quote! {
+                #pattern => __qser::__private::serialize_owned(#payload)
+            },
+        });
+    }
+
+    let serialize = serialize_impl(
+        input,
+        &bounded_where_clause,
+        quote! {
+            match __data {
+                #(
+                    #arm,
+                )*
+            }
+        },
+    )?;
+
+    Ok(quote! {
+        #[allow(non_upper_case_globals)]
+        #[allow(non_local_definitions)]
+        const #dummy: () = {
+            use #krate as __qser;
+
+            #serialize
+
+            #(
+                #items
+            )*
+        };
+    })
+}
+
+// Unit variants written as their discriminant, for `#[qser(repr = "int")]`.
+fn derive_enum_int(input: &DeriveInput, enumeration: &DataEnum) -> Result<TokenStream> {
+    let ident = &input.ident;
+    let dummy = Ident::new(
+        &format!("_IMPL_MINISERIALIZE_FOR_{}", ident),
+        Span::call_site(),
+    );
+    let krate = attr::crate_path(input)?;
+    let (_, this) = attr::this_type(input)?;
+
+    let var_ident = enumeration.variants.iter().map(|variant| &variant.ident);
+    let bounded_where_clause = ser_where_clause(input)?;
+    let serialize = serialize_impl(
+        input,
+        &bounded_where_clause,
+        quote! {
+            match __data {
+                #(
+                    #this::#var_ident => __qser::__private::discriminant(#this::#var_ident as i128),
+                )*
+            }
+        },
+    )?;
+
+    Ok(quote! {
+        #[allow(non_upper_case_globals)]
+        #[allow(non_local_definitions)]
+        const #dummy: () = {
+            use #krate as __qser;
+
+            #serialize
         };
     })
 }
@@ -0,0 +1,167 @@
+use std::collections::BTreeSet;
+use std::mem;
+
+use crate::de::{Deserialize, Map, Seq, Visitor};
+use crate::error::{Error, Result};
+
+make_place!(Place);
+
+/// A value buffered by the derived Deserialize impls of enums whose variant
+/// is not known until after its content has been read.
+///
+/// Unlike `json::Value`, strings borrowed from the input stay borrowed and map
+/// entries keep the order of the input, so that replaying the content into
+/// the variant behaves the same as deserializing the variant directly. Maps
+/// with repeated keys are rejected rather than letting one of the values win.
+pub enum Content<'de> {
+    Null,
+    Bool(bool),
+    Negative(i64),
+    Nonnegative(u64),
+    Float(f64),
+    Str(&'de str),
+    String(String),
+    Seq(Vec<Content<'de>>),
+    Map(Vec<(String, Content<'de>)>),
+}
+
+impl Drop for Content<'_> {
+    fn drop(&mut self) {
+        // Children are moved out to the stack before being dropped, so that
+        // each drop only ever sees an emptied seq or map and does not recurse.
+        let mut stack = Vec::new();
+        take_children(self, &mut stack);
+        while let Some(mut content) = stack.pop() {
+            take_children(&mut content, &mut stack);
+        }
+    }
+}
+
+fn take_children<'de>(content: &mut Content<'de>, stack: &mut Vec<Content<'de>>) {
+    match content {
+        Content::Seq(seq) => stack.append(seq),
+        Content::Map(map) => stack.extend(map.drain(..).map(|(_, v)| v)),
+        _ => {}
+    }
+}
+
+impl<'de> Deserialize<'de> for Content<'de> {
+    fn begin(out: &mut Option<Self>) -> &mut dyn Visitor<'de> {
+        Place::new(out)
+    }
+}
+
+impl<'de> Visitor<'de> for Place<Content<'de>> {
+    fn null(&mut self) -> Result<()> {
+        self.out = Some(Content::Null);
+        Ok(())
+    }
+
+    fn boolean(&mut self, b: bool) -> Result<()> {
+        self.out = Some(Content::Bool(b));
+        Ok(())
+    }
+
+    fn string(&mut self, s: &str) -> Result<()> {
+        self.out = Some(Content::String(s.to_owned()));
+        Ok(())
+    }
+
+    fn borrowed_string(&mut self, s: &'de str) -> Result<()> {
+        self.out = Some(Content::Str(s));
+        Ok(())
+    }
+
+    fn negative(&mut self, n: i64) -> Result<()> {
+        self.out = Some(Content::Negative(n));
+        Ok(())
+    }
+
+    fn nonnegative(&mut self, n: u64) -> Result<()> {
+        self.out = Some(Content::Nonnegative(n));
+        Ok(())
+    }
+
+    fn float(&mut self, n: f64) -> Result<()> {
+        self.out = Some(Content::Float(n));
+        Ok(())
+    }
+
+    fn seq(&mut self) -> Result<Box<dyn Seq<'de> + '_>> {
+        Ok(Box::new(SeqBuilder {
+            out: &mut self.out,
+            seq: Vec::new(),
+            element: None,
+        }))
+    }
+
+    fn map(&mut self) -> Result<Box<dyn Map<'de> + '_>> {
+        Ok(Box::new(MapBuilder {
+            out: &mut self.out,
+            map: Vec::new(),
+            seen: BTreeSet::new(),
+            key: None,
+            value: None,
+        }))
+    }
+}
+
+struct SeqBuilder<'a, 'de> {
+    out: &'a mut Option<Content<'de>>,
+    seq: Vec<Content<'de>>,
+    element: Option<Content<'de>>,
+}
+
+impl<'a, 'de> SeqBuilder<'a, 'de> {
+    fn shift(&mut self) {
+        if let Some(e) = self.element.take() {
+            self.seq.push(e);
+        }
+    }
+}
+
+impl<'a, 'de> Seq<'de> for SeqBuilder<'a, 'de> {
+    fn element(&mut self) -> Result<&mut dyn Visitor<'de>> {
+        self.shift();
+        Ok(Deserialize::begin(&mut self.element))
+    }
+
+    fn finish(&mut self) -> Result<()> {
+        self.shift();
+        *self.out = Some(Content::Seq(mem::take(&mut self.seq)));
+        Ok(())
+    }
+}
+
+struct MapBuilder<'a, 'de> {
+    out: &'a mut Option<Content<'de>>,
+    map: Vec<(String, Content<'de>)>,
+    seen: BTreeSet<String>,
+    key: Option<String>,
+    value: Option<Content<'de>>,
+}
+
+impl<'a, 'de> MapBuilder<'a, 'de> {
+    fn shift(&mut self) {
+        if let (Some(k), Some(v)) = (self.key.take(), self.value.take()) {
+            self.map.push((k, v));
+        }
+    }
+}
+
+impl<'a, 'de> Map<'de> for MapBuilder<'a, 'de> {
+    fn key(&mut self, k: &str) -> Result<&mut dyn Visitor<'de>> {
+        self.shift();
+        if !self.seen.insert(k.to_owned()) {
+            return Err(Error);
+        }
+        self.key = Some(k.to_owned());
+        Ok(Deserialize::begin(&mut self.value))
+    }
+
+    fn finish(&mut self) -> Result<()> {
+        self.shift();
+        *self.out = Some(Content::Map(mem::take(&mut self.map)));
+        Ok(())
+    }
+}
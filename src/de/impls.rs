@@ -1,5 +1,7 @@
+use std::borrow::Cow;
 use std::collections::{BTreeMap, HashMap};
 use std::hash::{BuildHasher, Hash};
+use std::marker::PhantomData;
 use std::mem;
 use std::str::FromStr;
 
@@ -7,10 +9,10 @@ use crate::Place;
 use crate::de::{Deserialize, Map, Seq, Visitor};
 use crate::error::{Error, Result};
 
-impl Deserialize for () {
-    fn begin(out: &mut Option<Self>) -> &mut dyn Visitor {
+impl<'de> Deserialize<'de> for () {
+    fn begin(out: &mut Option<Self>) -> &mut dyn Visitor<'de> {
         #[allow(non_local_definitions)]
-        impl Visitor for Place<()> {
+        impl<'de> Visitor<'de> for Place<()> {
             fn null(&mut self) -> Result<()> {
                 self.out = Some(());
                 Ok(())
@@ -20,10 +22,23 @@ impl Deserialize for () {
     }
 }
 
-impl Deserialize for bool {
-    fn begin(out: &mut Option<Self>) -> &mut dyn Visitor {
+impl<'de, T: ?Sized> Deserialize<'de> for PhantomData<T> {
+    fn begin(out: &mut Option<Self>) -> &mut dyn Visitor<'de> {
         #[allow(non_local_definitions)]
-        impl Visitor for Place<bool> {
+        impl<'de, T: ?Sized> Visitor<'de> for Place<PhantomData<T>> {
+            fn null(&mut self) -> Result<()> {
+                self.out = Some(PhantomData);
+                Ok(())
+            }
+        }
+        Place::new(out)
+    }
+}
+
+impl<'de> Deserialize<'de> for bool {
+    fn begin(out: &mut Option<Self>) -> &mut dyn Visitor<'de> {
+        #[allow(non_local_definitions)]
+        impl<'de> Visitor<'de> for Place<bool> {
             fn boolean(&mut self, b: bool) -> Result<()> {
                 self.out = Some(b);
                 Ok(())
@@ -33,10 +48,10 @@ impl Deserialize for bool {
     }
 }
 
-impl Deserialize for String {
-    fn begin(out: &mut Option<Self>) -> &mut dyn Visitor {
+impl<'de> Deserialize<'de> for String {
+    fn begin(out: &mut Option<Self>) -> &mut dyn Visitor<'de> {
         #[allow(non_local_definitions)]
-        impl Visitor for Place<String> {
+        impl<'de> Visitor<'de> for Place<String> {
             fn string(&mut self, s: &str) -> Result<()> {
                 self.out = Some(s.to_owned());
                 Ok(())
@@ -46,12 +61,42 @@ impl Deserialize for String {
     }
 }
 
+/// Only succeeds on strings borrowed from the input, that is strings without
+/// escape sequences in JSON.
+impl<'de: 'a, 'a> Deserialize<'de> for &'a str {
+    fn begin(out: &mut Option<Self>) -> &mut dyn Visitor<'de> {
+        #[allow(non_local_definitions)]
+        impl<'de: 'a, 'a> Visitor<'de> for Place<&'a str> {
+            fn borrowed_string(&mut self, s: &'de str) -> Result<()> {
+                self.out = Some(s);
+                Ok(())
+            }
+        }
+        Place::new(out)
+    }
+}
+
+/// Always deserializes to `Cow::Owned`. Fields marked `#[serde(borrow)]` get
+/// `Cow::Borrowed` whenever the input allows it.
+impl<'de, 'a> Deserialize<'de> for Cow<'a, str> {
+    fn begin(out: &mut Option<Self>) -> &mut dyn Visitor<'de> {
+        #[allow(non_local_definitions)]
+        impl<'de, 'a> Visitor<'de> for Place<Cow<'a, str>> {
+            fn string(&mut self, s: &str) -> Result<()> {
+                self.out = Some(Cow::Owned(s.to_owned()));
+                Ok(())
+            }
+        }
+        Place::new(out)
+    }
+}
+
 macro_rules! signed {
     ($ty:ident) => {
-        impl Deserialize for $ty {
-            fn begin(out: &mut Option<Self>) -> &mut dyn Visitor {
+        impl<'de> Deserialize<'de> for $ty {
+            fn begin(out: &mut Option<Self>) -> &mut dyn Visitor<'de> {
                 #[allow(non_local_definitions)]
-                impl Visitor for Place<$ty> {
+                impl<'de> Visitor<'de> for Place<$ty> {
                     fn negative(&mut self, n: i64) -> Result<()> {
                         if n >= $ty::min_value() as i64 {
                             self.out = Some(n as $ty);
@@ -83,10 +128,10 @@ signed!(isize);
 
 macro_rules! unsigned {
     ($ty:ident) => {
-        impl Deserialize for $ty {
-            fn begin(out: &mut Option<Self>) -> &mut dyn Visitor {
+        impl<'de> Deserialize<'de> for $ty {
+            fn begin(out: &mut Option<Self>) -> &mut dyn Visitor<'de> {
                 #[allow(non_local_definitions)]
-                impl Visitor for Place<$ty> {
+                impl<'de> Visitor<'de> for Place<$ty> {
                     fn nonnegative(&mut self, n: u64) -> Result<()> {
                         if n <= $ty::max_value() as u64 {
                             self.out = Some(n as $ty);
@@ -109,10 +154,10 @@ unsigned!(usize);
 
 macro_rules! float {
     ($ty:ident) => {
-        impl Deserialize for $ty {
+        impl<'de> Deserialize<'de> for $ty {
             #[allow(non_local_definitions)]
-            fn begin(out: &mut Option<Self>) -> &mut dyn Visitor {
-                impl Visitor for Place<$ty> {
+            fn begin(out: &mut Option<Self>) -> &mut dyn Visitor<'de> {
+                impl<'de> Visitor<'de> for Place<$ty> {
                     fn negative(&mut self, n: i64) -> Result<()> {
                         self.out = Some(n as $ty);
                         Ok(())
@@ -136,10 +181,10 @@ macro_rules! float {
 float!(f32);
 float!(f64);
 
-impl<T: Deserialize> Deserialize for Box<T> {
-    fn begin(out: &mut Option<Self>) -> &mut dyn Visitor {
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for Box<T> {
+    fn begin(out: &mut Option<Self>) -> &mut dyn Visitor<'de> {
         #[allow(non_local_definitions)]
-        impl<T: Deserialize> Visitor for Place<Box<T>> {
+        impl<'de, T: Deserialize<'de>> Visitor<'de> for Place<Box<T>> {
             fn null(&mut self) -> Result<()> {
                 let mut out = None;
                 Deserialize::begin(&mut out).null()?;
@@ -161,6 +206,13 @@ impl<T: Deserialize> Deserialize for Box<T> {
                 Ok(())
             }
 
+            fn borrowed_string(&mut self, s: &'de str) -> Result<()> {
+                let mut out = None;
+                Deserialize::begin(&mut out).borrowed_string(s)?;
+                self.out = Some(Box::new(out.unwrap()));
+                Ok(())
+            }
+
             fn negative(&mut self, n: i64) -> Result<()> {
                 let mut out = None;
                 Deserialize::begin(&mut out).negative(n)?;
@@ -182,35 +234,37 @@ impl<T: Deserialize> Deserialize for Box<T> {
                 Ok(())
             }
 
-            fn seq(&mut self) -> Result<Box<dyn Seq + '_>> {
+            fn seq(&mut self) -> Result<Box<dyn Seq<'de> + '_>> {
                 let mut value = Box::new(None);
                 let ptr = careful!(&mut *value as &mut Option<T>);
-                Ok(Box::new(BoxSeq {
+                let seq = Box::new(BoxSeq {
                     out: &mut self.out,
                     value,
                     seq: Deserialize::begin(ptr).seq()?,
-                }))
+                });
+                Ok(careful!(seq as Box<dyn Seq<'de>>))
             }
 
-            fn map(&mut self) -> Result<Box<dyn Map + '_>> {
+            fn map(&mut self) -> Result<Box<dyn Map<'de> + '_>> {
                 let mut value = Box::new(None);
                 let ptr = careful!(&mut *value as &mut Option<T>);
-                Ok(Box::new(BoxMap {
+                let map = Box::new(BoxMap {
                     out: &mut self.out,
                     value,
                     map: Deserialize::begin(ptr).map()?,
-                }))
+                });
+                Ok(careful!(map as Box<dyn Map<'de>>))
             }
         }
 
-        struct BoxSeq<'a, T: 'a> {
+        struct BoxSeq<'a, 'de, T: 'a> {
             out: &'a mut Option<Box<T>>,
             value: Box<Option<T>>,
-            seq: Box<dyn Seq + 'a>,
+            seq: Box<dyn Seq<'de> + 'a>,
         }
 
-        impl<'a, T: Deserialize> Seq for BoxSeq<'a, T> {
-            fn element(&mut self) -> Result<&mut dyn Visitor> {
+        impl<'a, 'de, T: Deserialize<'de>> Seq<'de> for BoxSeq<'a, 'de, T> {
+            fn element(&mut self) -> Result<&mut dyn Visitor<'de>> {
                 self.seq.element()
             }
 
@@ -221,14 +275,14 @@ impl<T: Deserialize> Deserialize for Box<T> {
             }
         }
 
-        struct BoxMap<'a, T: 'a> {
+        struct BoxMap<'a, 'de, T: 'a> {
             out: &'a mut Option<Box<T>>,
             value: Box<Option<T>>,
-            map: Box<dyn Map + 'a>,
+            map: Box<dyn Map<'de> + 'a>,
         }
 
-        impl<'a, T: Deserialize> Map for BoxMap<'a, T> {
-            fn key(&mut self, k: &str) -> Result<&mut dyn Visitor> {
+        impl<'a, 'de, T: Deserialize<'de>> Map<'de> for BoxMap<'a, 'de, T> {
+            fn key(&mut self, k: &str) -> Result<&mut dyn Visitor<'de>> {
                 self.map.key(k)
             }
 
@@ -243,13 +297,13 @@ impl<T: Deserialize> Deserialize for Box<T> {
     }
 }
 
-impl<T: Deserialize> Deserialize for Option<T> {
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for Option<T> {
     fn default() -> Option<Self> {
         Some(None)
     }
-    fn begin(out: &mut Option<Self>) -> &mut dyn Visitor {
+    fn begin(out: &mut Option<Self>) -> &mut dyn Visitor<'de> {
         #[allow(non_local_definitions)]
-        impl<T: Deserialize> Visitor for Place<Option<T>> {
+        impl<'de, T: Deserialize<'de>> Visitor<'de> for Place<Option<T>> {
             fn null(&mut self) -> Result<()> {
                 self.out = Some(None);
                 Ok(())
@@ -265,6 +319,11 @@ impl<T: Deserialize> Deserialize for Option<T> {
                 Deserialize::begin(self.out.as_mut().unwrap()).string(s)
             }
 
+            fn borrowed_string(&mut self, s: &'de str) -> Result<()> {
+                self.out = Some(None);
+                Deserialize::begin(self.out.as_mut().unwrap()).borrowed_string(s)
+            }
+
             fn negative(&mut self, n: i64) -> Result<()> {
                 self.out = Some(None);
                 Deserialize::begin(self.out.as_mut().unwrap()).negative(n)
@@ -280,14 +339,16 @@ impl<T: Deserialize> Deserialize for Option<T> {
                 Deserialize::begin(self.out.as_mut().unwrap()).float(n)
             }
 
-            fn seq(&mut self) -> Result<Box<dyn Seq + '_>> {
+            fn seq(&mut self) -> Result<Box<dyn Seq<'de> + '_>> {
                 self.out = Some(None);
-                Deserialize::begin(self.out.as_mut().unwrap()).seq()
+                let seq = Deserialize::begin(self.out.as_mut().unwrap()).seq()?;
+                Ok(careful!(seq as Box<dyn Seq<'de>>))
             }
 
-            fn map(&mut self) -> Result<Box<dyn Map + '_>> {
+            fn map(&mut self) -> Result<Box<dyn Map<'de> + '_>> {
                 self.out = Some(None);
-                Deserialize::begin(self.out.as_mut().unwrap()).map()
+                let map = Deserialize::begin(self.out.as_mut().unwrap()).map()?;
+                Ok(careful!(map as Box<dyn Map<'de>>))
             }
         }
 
@@ -295,11 +356,11 @@ impl<T: Deserialize> Deserialize for Option<T> {
     }
 }
 
-impl<A: Deserialize, B: Deserialize> Deserialize for (A, B) {
-    fn begin(out: &mut Option<Self>) -> &mut dyn Visitor {
+impl<'de, A: Deserialize<'de>, B: Deserialize<'de>> Deserialize<'de> for (A, B) {
+    fn begin(out: &mut Option<Self>) -> &mut dyn Visitor<'de> {
         #[allow(non_local_definitions)]
-        impl<A: Deserialize, B: Deserialize> Visitor for Place<(A, B)> {
-            fn seq(&mut self) -> Result<Box<dyn Seq + '_>> {
+        impl<'de, A: Deserialize<'de>, B: Deserialize<'de>> Visitor<'de> for Place<(A, B)> {
+            fn seq(&mut self) -> Result<Box<dyn Seq<'de> + '_>> {
                 Ok(Box::new(TupleBuilder {
                     out: &mut self.out,
                     tuple: (None, None),
@@ -312,8 +373,8 @@ impl<A: Deserialize, B: Deserialize> Deserialize for (A, B) {
             tuple: (Option<A>, Option<B>),
         }
 
-        impl<'a, A: Deserialize, B: Deserialize> Seq for TupleBuilder<'a, A, B> {
-            fn element(&mut self) -> Result<&mut dyn Visitor> {
+        impl<'a, 'de, A: Deserialize<'de>, B: Deserialize<'de>> Seq<'de> for TupleBuilder<'a, A, B> {
+            fn element(&mut self) -> Result<&mut dyn Visitor<'de>> {
                 if self.tuple.0.is_none() {
                     Ok(Deserialize::begin(&mut self.tuple.0))
                 } else if self.tuple.1.is_none() {
@@ -337,11 +398,11 @@ impl<A: Deserialize, B: Deserialize> Deserialize for (A, B) {
     }
 }
 
-impl<T: Deserialize> Deserialize for Vec<T> {
-    fn begin(out: &mut Option<Self>) -> &mut dyn Visitor {
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for Vec<T> {
+    fn begin(out: &mut Option<Self>) -> &mut dyn Visitor<'de> {
         #[allow(non_local_definitions)]
-        impl<T: Deserialize> Visitor for Place<Vec<T>> {
-            fn seq(&mut self) -> Result<Box<dyn Seq + '_>> {
+        impl<'de, T: Deserialize<'de>> Visitor<'de> for Place<Vec<T>> {
+            fn seq(&mut self) -> Result<Box<dyn Seq<'de> + '_>> {
                 Ok(Box::new(VecBuilder {
                     out: &mut self.out,
                     vec: Vec::new(),
@@ -364,8 +425,8 @@ impl<T: Deserialize> Deserialize for Vec<T> {
             }
         }
 
-        impl<'a, T: Deserialize> Seq for VecBuilder<'a, T> {
-            fn element(&mut self) -> Result<&mut dyn Visitor> {
+        impl<'a, 'de, T: Deserialize<'de>> Seq<'de> for VecBuilder<'a, T> {
+            fn element(&mut self) -> Result<&mut dyn Visitor<'de>> {
                 self.shift();
                 Ok(Deserialize::begin(&mut self.element))
             }
@@ -381,21 +442,21 @@ impl<T: Deserialize> Deserialize for Vec<T> {
     }
 }
 
-impl<K, V, H> Deserialize for HashMap<K, V, H>
+impl<'de, K, V, H> Deserialize<'de> for HashMap<K, V, H>
 where
     K: FromStr + Hash + Eq,
-    V: Deserialize,
+    V: Deserialize<'de>,
     H: BuildHasher + Default,
 {
-    fn begin(out: &mut Option<Self>) -> &mut dyn Visitor {
+    fn begin(out: &mut Option<Self>) -> &mut dyn Visitor<'de> {
         #[allow(non_local_definitions)]
-        impl<K, V, H> Visitor for Place<HashMap<K, V, H>>
+        impl<'de, K, V, H> Visitor<'de> for Place<HashMap<K, V, H>>
         where
             K: FromStr + Hash + Eq,
-            V: Deserialize,
+            V: Deserialize<'de>,
             H: BuildHasher + Default,
         {
-            fn map(&mut self) -> Result<Box<dyn Map + '_>> {
+            fn map(&mut self) -> Result<Box<dyn Map<'de> + '_>> {
                 Ok(Box::new(MapBuilder {
                     out: &mut self.out,
                     map: HashMap::with_hasher(H::default()),
@@ -420,13 +481,13 @@ where
             }
         }
 
-        impl<'a, K, V, H> Map for MapBuilder<'a, K, V, H>
+        impl<'a, 'de, K, V, H> Map<'de> for MapBuilder<'a, K, V, H>
         where
             K: FromStr + Hash + Eq,
-            V: Deserialize,
+            V: Deserialize<'de>,
             H: BuildHasher + Default,
         {
-            fn key(&mut self, k: &str) -> Result<&mut dyn Visitor> {
+            fn key(&mut self, k: &str) -> Result<&mut dyn Visitor<'de>> {
                 self.shift();
                 self.key = Some(match K::from_str(k) {
                     Ok(key) => key,
@@ -447,11 +508,11 @@ where
     }
 }
 
-impl<K: FromStr + Ord, V: Deserialize> Deserialize for BTreeMap<K, V> {
-    fn begin(out: &mut Option<Self>) -> &mut dyn Visitor {
+impl<'de, K: FromStr + Ord, V: Deserialize<'de>> Deserialize<'de> for BTreeMap<K, V> {
+    fn begin(out: &mut Option<Self>) -> &mut dyn Visitor<'de> {
         #[allow(non_local_definitions)]
-        impl<K: FromStr + Ord, V: Deserialize> Visitor for Place<BTreeMap<K, V>> {
-            fn map(&mut self) -> Result<Box<dyn Map + '_>> {
+        impl<'de, K: FromStr + Ord, V: Deserialize<'de>> Visitor<'de> for Place<BTreeMap<K, V>> {
+            fn map(&mut self) -> Result<Box<dyn Map<'de> + '_>> {
                 Ok(Box::new(MapBuilder {
                     out: &mut self.out,
                     map: BTreeMap::new(),
@@ -476,8 +537,8 @@ impl<K: FromStr + Ord, V: Deserialize> Deserialize for BTreeMap<K, V> {
             }
         }
 
-        impl<'a, K: FromStr + Ord, V: Deserialize> Map for MapBuilder<'a, K, V> {
-            fn key(&mut self, k: &str) -> Result<&mut dyn Visitor> {
+        impl<'a, 'de, K: FromStr + Ord, V: Deserialize<'de>> Map<'de> for MapBuilder<'a, K, V> {
+            fn key(&mut self, k: &str) -> Result<&mut dyn Visitor<'de>> {
                 self.shift();
                 self.key = Some(match K::from_str(k) {
                     Ok(key) => key,
@@ -37,15 +37,15 @@
 //! // output place.
 //! //
 //! // These methods may perform validation and decide to return an error.
-//! impl Visitor for Place<MyBoolean> {
+//! impl<'de> Visitor<'de> for Place<MyBoolean> {
 //!     fn boolean(&mut self, b: bool) -> Result<()> {
 //!         self.out = Some(MyBoolean(b));
 //!         Ok(())
 //!     }
 //! }
 //!
-//! impl Deserialize for MyBoolean {
-//!     fn begin(out: &mut Option<Self>) -> &mut dyn Visitor {
+//! impl<'de> Deserialize<'de> for MyBoolean {
+//!     fn begin(out: &mut Option<Self>) -> &mut dyn Visitor<'de> {
 //!         // All Deserialize impls will look exactly like this. There is no
 //!         // other correct implementation of Deserialize.
 //!         Place::new(out)
@@ -67,8 +67,8 @@
 //!
 //! struct MyVec<T>(Vec<T>);
 //!
-//! impl<T: Deserialize> Visitor for Place<MyVec<T>> {
-//!     fn seq(&mut self) -> Result<Box<dyn Seq + '_>> {
+//! impl<'de, T: Deserialize<'de>> Visitor<'de> for Place<MyVec<T>> {
+//!     fn seq(&mut self) -> Result<Box<dyn Seq<'de> + '_>> {
 //!         Ok(Box::new(VecBuilder {
 //!             out: &mut self.out,
 //!             vec: Vec::new(),
@@ -86,8 +86,8 @@
 //!     element: Option<T>,
 //! }
 //!
-//! impl<'a, T: Deserialize> Seq for VecBuilder<'a, T> {
-//!     fn element(&mut self) -> Result<&mut dyn Visitor> {
+//! impl<'a, 'de, T: Deserialize<'de>> Seq<'de> for VecBuilder<'a, T> {
+//!     fn element(&mut self) -> Result<&mut dyn Visitor<'de>> {
 //!         // Free up the place by transfering the most recent element
 //!         // into self.vec.
 //!         self.vec.extend(self.element.take());
@@ -105,8 +105,8 @@
 //!     }
 //! }
 //!
-//! impl<T: Deserialize> Deserialize for MyVec<T> {
-//!     fn begin(out: &mut Option<Self>) -> &mut dyn Visitor {
+//! impl<'de, T: Deserialize<'de>> Deserialize<'de> for MyVec<T> {
+//!     fn begin(out: &mut Option<Self>) -> &mut dyn Visitor<'de> {
 //!         // As mentioned, all Deserialize impls will look like this.
 //!         Place::new(out)
 //!     }
@@ -130,8 +130,8 @@
 //!     message: String,
 //! }
 //!
-//! impl Visitor for Place<Demo> {
-//!     fn map(&mut self) -> Result<Box<dyn Map + '_>> {
+//! impl<'de> Visitor<'de> for Place<Demo> {
+//!     fn map(&mut self) -> Result<Box<dyn Map<'de> + '_>> {
 //!         // Like for sequences, we produce a builder that can hand out places
 //!         // to write one struct field at a time.
 //!         Ok(Box::new(DemoBuilder {
@@ -148,8 +148,8 @@
 //!     out: &'a mut Option<Demo>,
 //! }
 //!
-//! impl<'a> Map for DemoBuilder<'a> {
-//!     fn key(&mut self, k: &str) -> Result<&mut dyn Visitor> {
+//! impl<'a, 'de> Map<'de> for DemoBuilder<'a> {
+//!     fn key(&mut self, k: &str) -> Result<&mut dyn Visitor<'de>> {
 //!         // Figure out which field is being deserialized and return a place
 //!         // to write it.
 //!         //
@@ -174,8 +174,8 @@
 //!     }
 //! }
 //!
-//! impl Deserialize for Demo {
-//!     fn begin(out: &mut Option<Self>) -> &mut dyn Visitor {
+//! impl<'de> Deserialize<'de> for Demo {
+//!     fn begin(out: &mut Option<Self>) -> &mut dyn Visitor<'de> {
 //!         // All Deserialize impls look like this.
 //!         Place::new(out)
 //!     }
@@ -189,7 +189,10 @@ use crate::error::{Error, Result};
 /// Trait for data structures that can be deserialized from a JSON string.
 ///
 /// [Refer to the module documentation for examples.][::de]
-pub trait Deserialize: Sized {
+///
+/// The `'de` lifetime is that of the input, which types such as `&'de str`
+/// may borrow from.
+pub trait Deserialize<'de>: Sized {
     /// The only correct implementation of this method is:
     ///
     /// ```rust
@@ -198,15 +201,15 @@ pub trait Deserialize: Sized {
     /// #
     /// # make_place!(Place);
     /// # struct S;
-    /// # impl Visitor for Place<S> {}
+    /// # impl<'de> Visitor<'de> for Place<S> {}
     /// #
-    /// # impl Deserialize for S {
-    /// fn begin(out: &mut Option<Self>) -> &mut dyn Visitor {
+    /// # impl<'de> Deserialize<'de> for S {
+    /// fn begin(out: &mut Option<Self>) -> &mut dyn Visitor<'de> {
     ///     Place::new(out)
     /// }
     /// # }
     /// ```
-    fn begin(out: &mut Option<Self>) -> &mut dyn Visitor;
+    fn begin(out: &mut Option<Self>) -> &mut dyn Visitor<'de>;
 
     // Not public API. This method is only intended for Option<T>, should not
     // need to be implemented outside of this crate.
@@ -216,10 +219,16 @@ pub trait Deserialize: Sized {
     }
 }
 
+/// Trait for data structures that can be deserialized from any JSON string,
+/// because they do not borrow from it.
+pub trait DeserializeOwned: for<'de> Deserialize<'de> {}
+
+impl<T> DeserializeOwned for T where T: for<'de> Deserialize<'de> {}
+
 /// Trait that can write data into an output place.
 ///
 /// [Refer to the module documentation for examples.][::de]
-pub trait Visitor {
+pub trait Visitor<'de> {
     fn null(&mut self) -> Result<()> {
         Err(Error)
     }
@@ -234,6 +243,12 @@ pub trait Visitor {
         Err(Error)
     }
 
+    /// A string which lives as long as the input, and so may be borrowed
+    /// rather than copied. Forwards to `string` unless overridden.
+    fn borrowed_string(&mut self, s: &'de str) -> Result<()> {
+        self.string(s)
+    }
+
     fn negative(&mut self, n: i64) -> Result<()> {
         let _ = n;
         Err(Error)
@@ -249,11 +264,11 @@ pub trait Visitor {
         Err(Error)
     }
 
-    fn seq(&mut self) -> Result<Box<dyn Seq + '_>> {
+    fn seq(&mut self) -> Result<Box<dyn Seq<'de> + '_>> {
         Err(Error)
     }
 
-    fn map(&mut self) -> Result<Box<dyn Map + '_>> {
+    fn map(&mut self) -> Result<Box<dyn Map<'de> + '_>> {
         Err(Error)
     }
 }
@@ -261,15 +276,15 @@ pub trait Visitor {
 /// Trait that can hand out places to write sequence elements.
 ///
 /// [Refer to the module documentation for examples.][::de]
-pub trait Seq {
-    fn element(&mut self) -> Result<&mut dyn Visitor>;
+pub trait Seq<'de> {
+    fn element(&mut self) -> Result<&mut dyn Visitor<'de>>;
     fn finish(&mut self) -> Result<()>;
 }
 
 /// Trait that can hand out places to write values of a map.
 ///
 /// [Refer to the module documentation for examples.][::de]
-pub trait Map {
-    fn key(&mut self, k: &str) -> Result<&mut dyn Visitor>;
+pub trait Map<'de> {
+    fn key(&mut self, k: &str) -> Result<&mut dyn Visitor<'de>>;
     fn finish(&mut self) -> Result<()>;
 }
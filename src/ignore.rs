@@ -1,15 +1,15 @@
 use crate::de::{Map, Seq, Visitor};
 use crate::error::Result;
 
-impl dyn Visitor {
-    pub fn ignore() -> &'static mut dyn Visitor {
+impl<'de> dyn Visitor<'de> {
+    pub fn ignore<'a>() -> &'a mut dyn Visitor<'de> {
         careful!(&mut Ignore as &mut Ignore)
     }
 }
 
 struct Ignore;
 
-impl Visitor for Ignore {
+impl<'de> Visitor<'de> for Ignore {
     fn null(&mut self) -> Result<()> {
         Ok(())
     }
@@ -34,17 +34,17 @@ impl Visitor for Ignore {
         Ok(())
     }
 
-    fn seq(&mut self) -> Result<Box<dyn Seq + '_>> {
+    fn seq(&mut self) -> Result<Box<dyn Seq<'de> + '_>> {
         Ok(Box::new(Ignore))
     }
 
-    fn map(&mut self) -> Result<Box<dyn Map + '_>> {
+    fn map(&mut self) -> Result<Box<dyn Map<'de> + '_>> {
         Ok(Box::new(Ignore))
     }
 }
 
-impl Seq for Ignore {
-    fn element(&mut self) -> Result<&mut dyn Visitor> {
+impl<'de> Seq<'de> for Ignore {
+    fn element(&mut self) -> Result<&mut dyn Visitor<'de>> {
         Ok(<dyn Visitor>::ignore())
     }
 
@@ -53,8 +53,8 @@ impl Seq for Ignore {
     }
 }
 
-impl Map for Ignore {
-    fn key(&mut self, _k: &str) -> Result<&mut dyn Visitor> {
+impl<'de> Map<'de> for Ignore {
+    fn key(&mut self, _k: &str) -> Result<&mut dyn Visitor<'de>> {
         Ok(<dyn Visitor>::ignore())
     }
 
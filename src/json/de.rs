@@ -1,3 +1,4 @@
+use std::ops::Deref;
 use std::{char, mem, str};
 
 use self::Event::*;
@@ -24,7 +25,7 @@ use crate::error::{Error, Result};
 ///     Ok(())
 /// }
 /// ```
-pub fn from_str<T: Deserialize>(j: &str) -> Result<T> {
+pub fn from_str<'de, T: Deserialize<'de>>(j: &'de str) -> Result<T> {
     let mut out = None;
     from_str_impl(j, T::begin(&mut out))?;
     out.ok_or(Error)
@@ -34,12 +35,12 @@ struct Deserializer<'a, 'b> {
     input: &'a [u8],
     pos: usize,
     buffer: Vec<u8>,
-    stack: Vec<(&'b mut dyn Visitor, Layer<'b>)>,
+    stack: Vec<(&'b mut dyn Visitor<'a>, Layer<'a, 'b>)>,
 }
 
-enum Layer<'a> {
-    Seq(Box<dyn Seq + 'a>),
-    Map(Box<dyn Map + 'a>),
+enum Layer<'de, 'a> {
+    Seq(Box<dyn Seq<'de> + 'a>),
+    Map(Box<dyn Map<'de> + 'a>),
 }
 
 impl<'a, 'b> Drop for Deserializer<'a, 'b> {
@@ -52,7 +53,7 @@ impl<'a, 'b> Drop for Deserializer<'a, 'b> {
 }
 
 #[inline(never)]
-fn from_str_impl(j: &str, mut visitor: &mut dyn Visitor) -> Result<()> {
+fn from_str_impl<'de>(j: &'de str, mut visitor: &mut dyn Visitor<'de>) -> Result<()> {
     let mut de = Deserializer {
         input: j.as_bytes(),
         pos: 0,
@@ -82,16 +83,20 @@ fn from_str_impl(j: &str, mut visitor: &mut dyn Visitor) -> Result<()> {
                 visitor.float(n)?;
                 None
             }
-            Str(s) => {
+            Str(Reference::Borrowed(s)) => {
+                visitor.borrowed_string(s)?;
+                None
+            }
+            Str(Reference::Copied(s)) => {
                 visitor.string(s)?;
                 None
             }
             SeqStart => {
-                let seq = careful!(visitor.seq()? as Box<dyn Seq>);
+                let seq = careful!(visitor.seq()? as Box<dyn Seq<'de>>);
                 Some(Layer::Seq(seq))
             }
             MapStart => {
-                let map = careful!(visitor.map()? as Box<dyn Map>);
+                let map = careful!(visitor.map()? as Box<dyn Map<'de>>);
                 Some(Layer::Map(map))
             }
         };
@@ -145,7 +150,7 @@ fn from_str_impl(j: &str, mut visitor: &mut dyn Visitor) -> Result<()> {
 
         match layer {
             Layer::Seq(mut seq) => {
-                let inner = careful!(seq.element()? as &mut dyn Visitor);
+                let inner = careful!(seq.element()? as &mut dyn Visitor<'de>);
                 let outer = mem::replace(&mut visitor, inner);
                 de.stack.push((outer, Layer::Seq(seq)));
             }
@@ -156,7 +161,7 @@ fn from_str_impl(j: &str, mut visitor: &mut dyn Visitor) -> Result<()> {
                 }
                 let inner = {
                     let k = de.parse_str()?;
-                    careful!(map.key(k)? as &mut dyn Visitor)
+                    careful!(map.key(&k)? as &mut dyn Visitor<'de>)
                 };
                 match de.parse_whitespace() {
                     Some(b':') => de.bump(),
@@ -174,10 +179,10 @@ fn from_str_impl(j: &str, mut visitor: &mut dyn Visitor) -> Result<()> {
     }
 }
 
-enum Event<'a> {
+enum Event<'de, 's> {
     Null,
     Bool(bool),
-    Str(&'a str),
+    Str(Reference<'de, 's>),
     Negative(i64),
     Nonnegative(u64),
     Float(f64),
@@ -185,6 +190,24 @@ enum Event<'a> {
     MapStart,
 }
 
+// A string either borrowed from the input, or decoded into the scratch buffer
+// because it contained escapes.
+enum Reference<'de, 's> {
+    Borrowed(&'de str),
+    Copied(&'s str),
+}
+
+impl<'de, 's> Deref for Reference<'de, 's> {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        match self {
+            Reference::Borrowed(s) => s,
+            Reference::Copied(s) => s,
+        }
+    }
+}
+
 macro_rules! overflow {
     ($a:ident * 10 + $b:ident, $c:expr) => {
         $a >= $c / 10 && ($a > $c / 10 || $b > $c % 10)
@@ -222,7 +245,7 @@ impl<'a, 'b> Deserializer<'a, 'b> {
         self.pos += 1;
     }
 
-    fn parse_str(&mut self) -> Result<&str> {
+    fn parse_str(&mut self) -> Result<Reference<'a, '_>> {
         fn result(bytes: &[u8]) -> &str {
             // The input is assumed to be valid UTF-8 and the \u-escapes are
             // checked along the way, so don't need to check here.
@@ -245,13 +268,14 @@ impl<'a, 'b> Deserializer<'a, 'b> {
                     if self.buffer.is_empty() {
                         // Fast path: return a slice of the raw JSON without any
                         // copying.
-                        let borrowed = &self.input[start..self.pos];
+                        let input = self.input;
+                        let borrowed = &input[start..self.pos];
                         self.pos += 1;
-                        return Ok(result(borrowed));
+                        return Ok(Reference::Borrowed(result(borrowed)));
                     } else {
                         self.buffer.extend_from_slice(&self.input[start..self.pos]);
                         self.pos += 1;
-                        return Ok(result(&self.buffer));
+                        return Ok(Reference::Copied(result(&self.buffer)));
                     }
                 }
                 b'\\' => {
@@ -384,7 +408,7 @@ impl<'a, 'b> Deserializer<'a, 'b> {
         Ok(())
     }
 
-    fn parse_integer(&mut self, nonnegative: bool, first_digit: u8) -> Result<Event> {
+    fn parse_integer(&mut self, nonnegative: bool, first_digit: u8) -> Result<Event<'a, '_>> {
         match first_digit {
             b'0' => {
                 // There can be only one leading '0'.
@@ -454,7 +478,7 @@ impl<'a, 'b> Deserializer<'a, 'b> {
         }
     }
 
-    fn parse_number(&mut self, nonnegative: bool, significand: u64) -> Result<Event> {
+    fn parse_number(&mut self, nonnegative: bool, significand: u64) -> Result<Event<'a, '_>> {
         match self.peek_or_nul() {
             b'.' => self.parse_decimal(nonnegative, significand, 0).map(Float),
             b'e' | b'E' => self.parse_exponent(nonnegative, significand, 0).map(Float),
@@ -581,7 +605,7 @@ impl<'a, 'b> Deserializer<'a, 'b> {
         Ok(if nonnegative { 0.0 } else { -0.0 })
     }
 
-    fn event(&mut self) -> Result<Event> {
+    fn event(&mut self) -> Result<Event<'a, '_>> {
         let peek = match self.parse_whitespace() {
             Some(b) => b,
             None => return Err(Error),
@@ -110,15 +110,15 @@ impl Serialize for RawValue {
     }
 }
 
-impl Deserialize for Box<RawValue> {
-    fn begin(out: &mut Option<Self>) -> &mut dyn Visitor {
+impl<'de> Deserialize<'de> for Box<RawValue> {
+    fn begin(out: &mut Option<Self>) -> &mut dyn Visitor<'de> {
         struct RawValueBuilder<'a> {
             json: Option<String>, // TODO: impl deserialize for box<str>
             out: &'a mut Option<Box<RawValue>>,
         }
 
-        impl<'a> de::Map for RawValueBuilder<'a> {
-            fn key(&mut self, k: &str) -> Result<&mut dyn Visitor> {
+        impl<'a, 'de> de::Map<'de> for RawValueBuilder<'a> {
+            fn key(&mut self, k: &str) -> Result<&mut dyn Visitor<'de>> {
                 match k {
                     TOKEN => Ok(Deserialize::begin(&mut self.json)),
                     _ => Ok(<dyn Visitor>::ignore()),
@@ -133,8 +133,8 @@ impl Deserialize for Box<RawValue> {
         }
 
         #[allow(non_local_definitions)]
-        impl Visitor for Place<Box<RawValue>> {
-            fn map(&mut self) -> Result<Box<dyn de::Map + '_>> {
+        impl<'de> Visitor<'de> for Place<Box<RawValue>> {
+            fn map(&mut self) -> Result<Box<dyn de::Map<'de> + '_>> {
                 Ok(Box::new(RawValueBuilder {
                     json: None,
                     out: &mut self.out,
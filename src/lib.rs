@@ -109,11 +109,6 @@
 //! formats, but it is not a goal to enable that through what this library
 //! exposes.
 //!
-//! ## <font color="#C0C0C0">Different:</font> Structs only
-//!
-//! The qser derive macros will refuse anything other than a braced struct
-//! with named fields. Enums and tuple structs are not supported.
-//!
 //! ## <font color="#C0C0C0">Different:</font> No customization
 //!
 //! Serde has tons of knobs for configuring the derived serialization and
@@ -121,9 +116,10 @@
 //! configurability you can handwrite arbitrarily complicated implementations of
 //! its traits.
 //!
-//! qser provides just one attribute which is `rename`, and severely
-//! restricts the kinds of on-the-fly manipulation that are possible in custom
-//! impls. If you need any of this, use Serde -- it's a great library.
+//! qser understands only a subset of Serde's attributes, such as `rename` and
+//! `default`, and severely restricts the kinds of on-the-fly manipulation
+//! that are possible in custom impls. If you need any of this, use Serde --
+//! it's a great library.
 
 #![doc(html_root_url = "https://docs.rs/qser/0.1.13")]
 #![allow(
@@ -158,6 +154,7 @@ mod careful;
 #[macro_use]
 mod place;
 
+mod content;
 mod error;
 mod ignore;
 
@@ -165,6 +162,11 @@ pub mod de;
 pub mod json;
 pub mod ser;
 
+// Not public API. Used by the code generated by the derive macros.
+#[doc(hidden)]
+#[path = "private.rs"]
+pub mod __private;
+
 #[doc(inline)]
 pub use crate::de::Deserialize;
 pub use crate::error::{Error, Result};
@@ -0,0 +1,648 @@
+use std::borrow::Cow;
+use std::collections::BTreeSet;
+use std::marker::PhantomData;
+use std::slice;
+
+pub use crate::content::Content;
+use crate::de::{self, Deserialize, Seq, Visitor};
+use crate::error::{Error, Result};
+use crate::ser::{self, Fragment, Serialize};
+
+/// Streams the entries of a value that is serialized as part of an enclosing
+/// map, as for `#[serde(flatten)]` fields. Null values, such as a `None`
+/// option, contribute no entries.
+///
+/// # Panics
+///
+/// Types which are known not to be maps are rejected by the derive.
+/// Serializing cannot fail, so any other value that turns out to be neither a
+/// map nor null panics rather than being silently dropped.
+pub struct FlatMap<'a> {
+    // invariant: `next` borrows from `map` so must be dropped first
+    next: Option<(Cow<'a, str>, &'a dyn Serialize)>,
+    map: Option<Box<dyn ser::Map + 'a>>,
+}
+
+impl<'a> FlatMap<'a> {
+    pub fn new(value: &'a dyn Serialize) -> Self {
+        FlatMap {
+            next: None,
+            map: match value.begin() {
+                Fragment::Map(map) => Some(map),
+                Fragment::Null => None,
+                _ => panic!("cannot serialize flattened field: value must serialize as a map"),
+            },
+        }
+    }
+
+    // Checking for and taking the next entry are separate steps so that the
+    // caller can update its own state before handing out the entry.
+    pub fn has_next(&mut self) -> bool {
+        if self.next.is_some() {
+            return true;
+        }
+        if let Some(map) = &mut self.map {
+            // invariant: `map` must outlive the entry, which is only handed
+            // out while `self` is borrowed
+            self.next = careful!(map.next() as Option<(Cow<str>, &dyn Serialize)>);
+            if self.next.is_none() {
+                self.map = None;
+            }
+        }
+        self.next.is_some()
+    }
+
+    pub fn take_next(&mut self) -> Option<(Cow<'_, str>, &dyn Serialize)> {
+        self.next.take()
+    }
+}
+
+/// Serializes a value through a `#[serde(serialize_with = "...")]` hook.
+pub struct SerializeWith<'a, T: ?Sized> {
+    value: &'a T,
+    serialize: fn(&T) -> Fragment,
+}
+
+impl<'a, T: ?Sized> SerializeWith<'a, T> {
+    pub fn new(value: &'a T, serialize: fn(&T) -> Fragment) -> Self {
+        SerializeWith { value, serialize }
+    }
+}
+
+impl<'a, T: ?Sized> Serialize for SerializeWith<'a, T> {
+    fn begin(&self) -> Fragment<'_> {
+        (self.serialize)(self.value)
+    }
+}
+
+/// Serializes a temporary value, as for `#[serde(into = "...")]`. The value
+/// is kept alive by the returned fragment for as long as it is needed.
+pub fn serialize_owned<'a, T: Serialize + 'a>(value: T) -> Fragment<'a> {
+    let value = Box::new(value);
+    // invariant: `value` must outlive the fragment, so is moved into whatever
+    // stream is returned
+    match careful!(value.begin() as Fragment) {
+        Fragment::Null => Fragment::Null,
+        Fragment::Bool(b) => Fragment::Bool(b),
+        Fragment::Str(s) => Fragment::Str(Cow::Owned(s.into_owned())),
+        Fragment::U64(n) => Fragment::U64(n),
+        Fragment::I64(n) => Fragment::I64(n),
+        Fragment::F64(n) => Fragment::F64(n),
+        Fragment::Seq(seq) => Fragment::Seq(Box::new(OwnedSeq { seq, value })),
+        Fragment::Map(map) => Fragment::Map(Box::new(OwnedMap { map, value })),
+    }
+}
+
+struct OwnedSeq<'a, T> {
+    // invariant: `seq` borrows from `value` so must be dropped first
+    seq: Box<dyn ser::Seq + 'a>,
+    #[allow(dead_code)]
+    value: Box<T>,
+}
+
+impl<'a, T> ser::Seq for OwnedSeq<'a, T> {
+    fn next(&mut self) -> Option<&dyn Serialize> {
+        self.seq.next()
+    }
+}
+
+struct OwnedMap<'a, T> {
+    // invariant: `map` borrows from `value` so must be dropped first
+    map: Box<dyn ser::Map + 'a>,
+    #[allow(dead_code)]
+    value: Box<T>,
+}
+
+impl<'a, T> ser::Map for OwnedMap<'a, T> {
+    fn next(&mut self) -> Option<(Cow<'_, str>, &dyn Serialize)> {
+        self.map.next()
+    }
+}
+
+/// Map with a single entry, as for the `{"Variant": payload}` encoding of
+/// externally tagged enum variants.
+pub struct Entry<T> {
+    key: &'static str,
+    value: T,
+    done: bool,
+}
+
+impl<T: Serialize> Entry<T> {
+    pub fn new(key: &'static str, value: T) -> Self {
+        Entry {
+            key,
+            value,
+            done: false,
+        }
+    }
+}
+
+impl<T: Serialize> ser::Map for Entry<T> {
+    fn next(&mut self) -> Option<(Cow<'_, str>, &dyn Serialize)> {
+        if self.done {
+            return None;
+        }
+        self.done = true;
+        Some((Cow::Borrowed(self.key), &self.value))
+    }
+}
+
+/// Map of a tag entry followed by the entries of the content, as for
+/// internally tagged enums. Unit content contributes no entries.
+///
+/// # Panics
+///
+/// Content types which are known not to be maps are rejected by the derive.
+/// Serializing cannot fail, so any other content that turns out to be neither
+/// a map nor null panics rather than being silently dropped.
+pub struct TaggedMap<'a> {
+    tag: &'static str,
+    variant: &'static str,
+    tagged: bool,
+    content: Option<Box<dyn ser::Map + 'a>>,
+}
+
+impl<'a> TaggedMap<'a> {
+    pub fn new(tag: &'static str, variant: &'static str, content: Fragment<'a>) -> Self {
+        TaggedMap {
+            tag,
+            variant,
+            tagged: false,
+            content: match content {
+                Fragment::Map(map) => Some(map),
+                Fragment::Null => None,
+                _ => panic!(
+                    "cannot serialize variant `{}` with tag `{}`: content must serialize as a map",
+                    variant, tag,
+                ),
+            },
+        }
+    }
+}
+
+impl<'a> ser::Map for TaggedMap<'a> {
+    fn next(&mut self) -> Option<(Cow<'_, str>, &dyn Serialize)> {
+        if !self.tagged {
+            self.tagged = true;
+            return Some((Cow::Borrowed(self.tag), &self.variant));
+        }
+        self.content.as_mut()?.next()
+    }
+}
+
+/// Serializes the discriminant of a unit variant, as for
+/// `#[qser(repr = "int")]`.
+pub fn discriminant(n: i128) -> Fragment<'static> {
+    if n < 0 {
+        Fragment::I64(n as i64)
+    } else {
+        Fragment::U64(n as u64)
+    }
+}
+
+/// Builds a value out of entries of an enclosing map, as for
+/// `#[serde(flatten)]` fields. Repeated keys are rejected even if the value
+/// being built, such as a map, would accept them. If no entries were routed
+/// to the value, it is the type's default if it has one, such as `None` for an
+/// option.
+pub struct FlatBuilder<'de, T> {
+    // invariant: `map` borrows from `out` so must be dropped first
+    map: Option<Box<dyn de::Map<'de>>>,
+    out: Box<Option<T>>,
+    seen: BTreeSet<String>,
+}
+
+impl<'de, T: Deserialize<'de>> FlatBuilder<'de, T> {
+    pub fn new() -> Self {
+        FlatBuilder {
+            map: None,
+            out: Box::new(None),
+            seen: BTreeSet::new(),
+        }
+    }
+
+    fn map(&mut self) -> Result<&mut dyn de::Map<'de>> {
+        if self.map.is_none() {
+            let out = careful!(&mut *self.out as &mut Option<T>);
+            self.map = Some(careful!(T::begin(out).map()? as Box<dyn de::Map<'de>>));
+        }
+        Ok(&mut **self.map.as_mut().unwrap())
+    }
+
+    pub fn key(&mut self, k: &str) -> Result<&mut dyn Visitor<'de>> {
+        if !self.seen.insert(k.to_owned()) {
+            return Err(Error);
+        }
+        self.map()?.key(k)
+    }
+
+    pub fn finish(&mut self) -> Result<T> {
+        if self.map.is_none()
+            && let Some(default) = T::default()
+        {
+            return Ok(default);
+        }
+        self.map()?.finish()?;
+        self.map = None;
+        self.out.take().ok_or(Error)
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Default for FlatBuilder<'de, T> {
+    fn default() -> Self {
+        FlatBuilder::new()
+    }
+}
+
+make_place!(BorrowPlace);
+
+/// Place for a `#[serde(borrow)]` field of type `Cow<str>`, which keeps strings
+/// borrowed from the input instead of copying them.
+pub fn borrow_cow_str<'de: 'a, 'a, 'b>(
+    out: &'b mut Option<Cow<'a, str>>,
+) -> &'b mut dyn Visitor<'de> {
+    #[allow(non_local_definitions)]
+    impl<'de: 'a, 'a> Visitor<'de> for BorrowPlace<Cow<'a, str>> {
+        fn string(&mut self, s: &str) -> Result<()> {
+            self.out = Some(Cow::Owned(s.to_owned()));
+            Ok(())
+        }
+
+        fn borrowed_string(&mut self, s: &'de str) -> Result<()> {
+            self.out = Some(Cow::Borrowed(s));
+            Ok(())
+        }
+    }
+    BorrowPlace::new(out)
+}
+
+/// Conversion applied after deserializing an intermediate value, as for
+/// newtype structs, newtype variants and `#[serde(transparent)]`.
+pub trait Convert<'de> {
+    type From;
+    type Into;
+
+    /// Place for the intermediate value, usually `Deserialize::begin`.
+    fn begin(from: &mut Option<Self::From>) -> &mut dyn Visitor<'de>;
+
+    fn convert(from: Self::From) -> Result<Self::Into>;
+}
+
+/// Place that deserializes `C::From` and writes the converted value.
+#[repr(C)]
+pub struct ConvertPlace<'de, C: Convert<'de>> {
+    out: Option<C::Into>,
+    marker: PhantomData<&'de ()>,
+}
+
+impl<'de, C: Convert<'de>> ConvertPlace<'de, C> {
+    pub fn new(out: &mut Option<C::Into>) -> &mut Self {
+        unsafe { &mut *{ out as *mut Option<C::Into> as *mut ConvertPlace<'de, C> } }
+    }
+
+    fn write(&mut self, from: Option<C::From>) -> Result<()> {
+        self.out = Some(C::convert(from.ok_or(Error)?)?);
+        Ok(())
+    }
+}
+
+impl<'de, C: Convert<'de>> Visitor<'de> for ConvertPlace<'de, C> {
+    fn null(&mut self) -> Result<()> {
+        let mut from = None;
+        C::begin(&mut from).null()?;
+        self.write(from)
+    }
+
+    fn boolean(&mut self, b: bool) -> Result<()> {
+        let mut from = None;
+        C::begin(&mut from).boolean(b)?;
+        self.write(from)
+    }
+
+    fn string(&mut self, s: &str) -> Result<()> {
+        let mut from = None;
+        C::begin(&mut from).string(s)?;
+        self.write(from)
+    }
+
+    fn borrowed_string(&mut self, s: &'de str) -> Result<()> {
+        let mut from = None;
+        C::begin(&mut from).borrowed_string(s)?;
+        self.write(from)
+    }
+
+    fn negative(&mut self, n: i64) -> Result<()> {
+        let mut from = None;
+        C::begin(&mut from).negative(n)?;
+        self.write(from)
+    }
+
+    fn nonnegative(&mut self, n: u64) -> Result<()> {
+        let mut from = None;
+        C::begin(&mut from).nonnegative(n)?;
+        self.write(from)
+    }
+
+    fn float(&mut self, n: f64) -> Result<()> {
+        let mut from = None;
+        C::begin(&mut from).float(n)?;
+        self.write(from)
+    }
+
+    fn seq(&mut self) -> Result<Box<dyn Seq<'de> + '_>> {
+        let mut from = Box::new(None);
+        let ptr = careful!(&mut *from as &mut Option<C::From>);
+        Ok(Box::new(ConvertSeq::<C> {
+            seq: C::begin(ptr).seq()?,
+            from,
+            out: self,
+        }))
+    }
+
+    fn map(&mut self) -> Result<Box<dyn de::Map<'de> + '_>> {
+        let mut from = Box::new(None);
+        let ptr = careful!(&mut *from as &mut Option<C::From>);
+        Ok(Box::new(ConvertMap::<C> {
+            map: C::begin(ptr).map()?,
+            from,
+            out: self,
+        }))
+    }
+}
+
+struct ConvertSeq<'a, 'de, C: Convert<'de>> {
+    // invariant: `seq` borrows from `from` so must be dropped first
+    seq: Box<dyn Seq<'de> + 'a>,
+    from: Box<Option<C::From>>,
+    out: &'a mut ConvertPlace<'de, C>,
+}
+
+impl<'a, 'de, C: Convert<'de>> Seq<'de> for ConvertSeq<'a, 'de, C> {
+    fn element(&mut self) -> Result<&mut dyn Visitor<'de>> {
+        self.seq.element()
+    }
+
+    fn finish(&mut self) -> Result<()> {
+        self.seq.finish()?;
+        self.out.write(self.from.take())
+    }
+}
+
+struct ConvertMap<'a, 'de, C: Convert<'de>> {
+    // invariant: `map` borrows from `from` so must be dropped first
+    map: Box<dyn de::Map<'de> + 'a>,
+    from: Box<Option<C::From>>,
+    out: &'a mut ConvertPlace<'de, C>,
+}
+
+impl<'a, 'de, C: Convert<'de>> de::Map<'de> for ConvertMap<'a, 'de, C> {
+    fn key(&mut self, k: &str) -> Result<&mut dyn Visitor<'de>> {
+        self.map.key(k)
+    }
+
+    fn finish(&mut self) -> Result<()> {
+        self.map.finish()?;
+        self.out.write(self.from.take())
+    }
+}
+
+/// Gives the place for the payload of the variant with the given name.
+pub type Select<'de, T> = for<'a> fn(&'a mut Option<T>, &str) -> Result<&'a mut dyn Visitor<'de>>;
+
+/// Builds an internally tagged enum out of a map. Entries before the tag are
+/// buffered, then replayed into the map of the variant place given by
+/// `select` once the tag is known.
+pub struct TaggedBuilder<'a, 'de, T> {
+    tag: &'static str,
+    select: Select<'de, T>,
+    out: Option<&'a mut Option<T>>,
+    variant: Option<String>,
+    buffer: Vec<(String, Option<Content<'de>>)>,
+    buffered: BTreeSet<String>,
+    map: Option<Box<dyn de::Map<'de> + 'a>>,
+}
+
+impl<'a, 'de: 'a, T> TaggedBuilder<'a, 'de, T> {
+    pub fn new(tag: &'static str, out: &'a mut Option<T>, select: Select<'de, T>) -> Self {
+        TaggedBuilder {
+            tag,
+            select,
+            out: Some(out),
+            variant: None,
+            buffer: Vec::new(),
+            buffered: BTreeSet::new(),
+            map: None,
+        }
+    }
+
+    // The tag's value is only known once the next key or the end of the map
+    // is reached, so that is when the variant is opened.
+    fn open(&mut self) -> Result<()> {
+        let Some(variant) = self.variant.as_deref() else {
+            return Ok(());
+        };
+        if self.map.is_some() {
+            return Ok(());
+        }
+        let out = self.out.take().ok_or(Error)?;
+        let mut map = (self.select)(out, variant)?.map()?;
+        for (k, v) in self.buffer.drain(..) {
+            replay(&v.ok_or(Error)?, map.key(&k)?)?;
+        }
+        self.map = Some(map);
+        Ok(())
+    }
+}
+
+impl<'a, 'de: 'a, T> de::Map<'de> for TaggedBuilder<'a, 'de, T> {
+    fn key(&mut self, k: &str) -> Result<&mut dyn Visitor<'de>> {
+        if k == self.tag && self.variant.is_some() {
+            return Err(Error);
+        }
+        self.open()?;
+        match &mut self.map {
+            Some(map) => map.key(k),
+            None if k == self.tag => Ok(Deserialize::begin(&mut self.variant)),
+            None => {
+                // The variant may accept repeated keys, but they are no less
+                // ambiguous for having been buffered.
+                if !self.buffered.insert(k.to_owned()) {
+                    return Err(Error);
+                }
+                self.buffer.push((k.to_owned(), None));
+                Ok(Deserialize::begin(&mut self.buffer.last_mut().unwrap().1))
+            }
+        }
+    }
+
+    fn finish(&mut self) -> Result<()> {
+        self.open()?;
+        match &mut self.map {
+            Some(map) => map.finish(),
+            None => Err(Error),
+        }
+    }
+}
+
+/// Builds an adjacently tagged enum out of a map of the tag and the content,
+/// which may come in either order. Content that comes before the tag is
+/// buffered and replayed into the variant place given by `select`.
+pub struct AdjacentBuilder<'a, 'de, T> {
+    tag: &'static str,
+    content: &'static str,
+    deny_unknown_fields: bool,
+    select: Select<'de, T>,
+    out: &'a mut Option<T>,
+    variant: Option<String>,
+    seen_content: bool,
+    buffer: Option<Content<'de>>,
+}
+
+impl<'a, 'de, T> AdjacentBuilder<'a, 'de, T> {
+    pub fn new(
+        tag: &'static str,
+        content: &'static str,
+        deny_unknown_fields: bool,
+        out: &'a mut Option<T>,
+        select: Select<'de, T>,
+    ) -> Self {
+        AdjacentBuilder {
+            tag,
+            content,
+            deny_unknown_fields,
+            select,
+            out,
+            variant: None,
+            seen_content: false,
+            buffer: None,
+        }
+    }
+}
+
+impl<'a, 'de, T> de::Map<'de> for AdjacentBuilder<'a, 'de, T> {
+    fn key(&mut self, k: &str) -> Result<&mut dyn Visitor<'de>> {
+        if k == self.tag {
+            if self.variant.is_some() {
+                return Err(Error);
+            }
+            Ok(Deserialize::begin(&mut self.variant))
+        } else if k == self.content {
+            if self.seen_content {
+                return Err(Error);
+            }
+            self.seen_content = true;
+            match &self.variant {
+                Some(variant) => (self.select)(self.out, variant),
+                None => Ok(Deserialize::begin(&mut self.buffer)),
+            }
+        } else if self.deny_unknown_fields {
+            Err(Error)
+        } else {
+            Ok(<dyn Visitor>::ignore())
+        }
+    }
+
+    fn finish(&mut self) -> Result<()> {
+        let variant = self.variant.as_deref().ok_or(Error)?;
+        if !self.seen_content {
+            // Only unit variants may leave out the content.
+            (self.select)(self.out, variant)?.null()?;
+        } else if let Some(buffer) = &self.buffer {
+            replay(buffer, (self.select)(self.out, variant)?)?;
+        }
+        match self.out {
+            Some(_) => Ok(()),
+            None => Err(Error),
+        }
+    }
+}
+
+/// Tries a buffered value against each variant of an untagged enum in turn,
+/// keeping the first that accepts it.
+pub fn untagged<'de, T>(
+    content: &Content<'de>,
+    variants: &[&str],
+    select: Select<'de, T>,
+) -> Result<T> {
+    for variant in variants {
+        let mut out = None;
+        if replay(content, select(&mut out, variant)?).is_ok()
+            && let Some(out) = out
+        {
+            return Ok(out);
+        }
+    }
+    Err(Error)
+}
+
+/// Feeds a buffered value to a visitor as if it were being deserialized for
+/// the first time. Like the deserializer, this does not recurse.
+pub fn replay<'de>(content: &Content<'de>, visitor: &mut dyn Visitor<'de>) -> Result<()> {
+    let mut stack = ReplayStack(Vec::new());
+    let mut next = Some((content, visitor));
+
+    loop {
+        if let Some((content, visitor)) = next.take() {
+            match content {
+                Content::Null => visitor.null()?,
+                Content::Bool(b) => visitor.boolean(*b)?,
+                Content::Negative(n) => visitor.negative(*n)?,
+                Content::Nonnegative(n) => visitor.nonnegative(*n)?,
+                Content::Float(n) => visitor.float(*n)?,
+                Content::Str(s) => visitor.borrowed_string(s)?,
+                Content::String(s) => visitor.string(s)?,
+                Content::Seq(seq) => {
+                    let builder = careful!(visitor.seq()? as Box<dyn Seq<'de>>);
+                    stack.0.push(ReplayLayer::Seq(builder, seq.iter()));
+                }
+                Content::Map(map) => {
+                    let builder = careful!(visitor.map()? as Box<dyn de::Map<'de>>);
+                    stack.0.push(ReplayLayer::Map(builder, map.iter()));
+                }
+            }
+        }
+
+        match stack.0.last_mut() {
+            Some(ReplayLayer::Seq(seq, elements)) => match elements.next() {
+                Some(element) => {
+                    let visitor = careful!(seq.element()? as &mut dyn Visitor<'de>);
+                    next = Some((element, visitor));
+                }
+                None => {
+                    seq.finish()?;
+                    stack.0.pop();
+                }
+            },
+            Some(ReplayLayer::Map(map, entries)) => match entries.next() {
+                Some((k, v)) => {
+                    let visitor = careful!(map.key(k)? as &mut dyn Visitor<'de>);
+                    next = Some((v, visitor));
+                }
+                None => {
+                    map.finish()?;
+                    stack.0.pop();
+                }
+            },
+            None => return Ok(()),
+        }
+    }
+}
+
+enum ReplayLayer<'a, 'de> {
+    Seq(Box<dyn Seq<'de>>, slice::Iter<'a, Content<'de>>),
+    Map(
+        Box<dyn de::Map<'de>>,
+        slice::Iter<'a, (String, Content<'de>)>,
+    ),
+}
+
+struct ReplayStack<'a, 'de>(Vec<ReplayLayer<'a, 'de>>);
+
+impl<'a, 'de> Drop for ReplayStack<'a, 'de> {
+    fn drop(&mut self) {
+        // Drop layers in reverse order.
+        while !self.0.is_empty() {
+            self.0.pop();
+        }
+    }
+}
@@ -1,6 +1,7 @@
 use std::borrow::Cow;
 use std::collections::{BTreeMap, HashMap, btree_map, hash_map};
 use std::hash::{BuildHasher, Hash};
+use std::marker::PhantomData;
 use std::slice;
 
 use crate::private;
@@ -12,6 +13,12 @@ impl Serialize for () {
     }
 }
 
+impl<T: ?Sized> Serialize for PhantomData<T> {
+    fn begin(&self) -> Fragment {
+        Fragment::Null
+    }
+}
+
 impl Serialize for bool {
     fn begin(&self) -> Fragment {
         Fragment::Bool(*self)
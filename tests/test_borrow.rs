@@ -0,0 +1,146 @@
+use std::borrow::Cow;
+
+use qser::{Deserialize, json};
+
+#[derive(Deserialize, Debug)]
+struct Borrowed<'a, 'b> {
+    name: &'a str,
+    #[serde(borrow)]
+    cow: Cow<'b, str>,
+    owned: Cow<'b, str>,
+}
+
+#[test]
+fn test_borrowed_fields() {
+    let j = r#"{"name":"abc","cow":"def","owned":"ghi"}"#;
+    let borrowed: Borrowed = json::from_str(j).unwrap();
+    assert_eq!(borrowed.name, "abc");
+    assert!(matches!(borrowed.cow, Cow::Borrowed("def")));
+    assert!(matches!(borrowed.owned, Cow::Owned(_)));
+}
+
+#[test]
+fn test_escaped() {
+    let j = r#"{"name":"abc","cow":"d\nf","owned":"ghi"}"#;
+    let borrowed: Borrowed = json::from_str(j).unwrap();
+    assert!(matches!(borrowed.cow, Cow::Owned(ref s) if s == "d\nf"));
+
+    let j = r#"{"name":"a\nc","cow":"def","owned":"ghi"}"#;
+    assert!(json::from_str::<Borrowed>(j).is_err());
+}
+
+#[derive(Deserialize, Debug)]
+struct Explicit<'a> {
+    #[qser(borrow = "'a")]
+    cow: Cow<'a, str>,
+    opt: Option<&'a str>,
+    list: Vec<&'a str>,
+}
+
+#[test]
+fn test_nested() {
+    let j = r#"{"cow":"x","opt":"y","list":["z"]}"#;
+    let explicit: Explicit = json::from_str(j).unwrap();
+    assert!(matches!(explicit.cow, Cow::Borrowed("x")));
+    assert_eq!(explicit.opt, Some("y"));
+    assert_eq!(explicit.list, ["z"]);
+}
+
+#[derive(Deserialize, PartialEq, Debug)]
+enum External<'a> {
+    Newtype(&'a str),
+    Struct { s: &'a str },
+}
+
+#[test]
+fn test_externally_tagged() {
+    let j = r#"[{"Newtype":"a"},{"Struct":{"s":"b"}}]"#;
+    let list: Vec<External> = json::from_str(j).unwrap();
+    assert_eq!(list, [External::Newtype("a"), External::Struct { s: "b" }]);
+}
+
+#[derive(Deserialize, PartialEq, Debug)]
+#[serde(tag = "type")]
+enum Internal<'a> {
+    A {
+        s: &'a str,
+        #[serde(borrow)]
+        cow: Cow<'a, str>,
+    },
+}
+
+#[test]
+fn test_internally_tagged() {
+    for j in [
+        r#"{"type":"A","s":"x","cow":"y"}"#,
+        r#"{"s":"x","cow":"y","type":"A"}"#,
+    ] {
+        let Internal::A { s, cow } = json::from_str(j).unwrap();
+        assert_eq!(s, "x");
+        assert!(matches!(cow, Cow::Borrowed("y")));
+    }
+}
+
+#[derive(Deserialize, PartialEq, Debug)]
+#[serde(tag = "t", content = "c")]
+enum Adjacent<'a> {
+    A(&'a str),
+    B { s: Option<&'a str> },
+}
+
+#[test]
+fn test_adjacently_tagged() {
+    let j = r#"{"c":"x","t":"A"}"#;
+    assert_eq!(json::from_str::<Adjacent>(j).unwrap(), Adjacent::A("x"));
+    let j = r#"{"c":{"s":"x"},"t":"B"}"#;
+    assert_eq!(
+        json::from_str::<Adjacent>(j).unwrap(),
+        Adjacent::B { s: Some("x") }
+    );
+}
+
+#[derive(Deserialize, PartialEq, Debug)]
+#[serde(untagged)]
+#[allow(clippy::redundant_allocation)]
+enum Untagged<'a> {
+    Number(u64),
+    Str(&'a str),
+    Struct {
+        #[serde(borrow)]
+        cow: Cow<'a, str>,
+        boxed: Option<Box<&'a str>>,
+    },
+}
+
+#[test]
+fn test_untagged() {
+    assert_eq!(
+        json::from_str::<Untagged>("1").unwrap(),
+        Untagged::Number(1)
+    );
+    assert_eq!(
+        json::from_str::<Untagged>(r#""x""#).unwrap(),
+        Untagged::Str("x")
+    );
+    let j = r#"{"cow":"y","boxed":"z"}"#;
+    let Untagged::Struct { cow, boxed } = json::from_str(j).unwrap() else {
+        panic!("expected struct");
+    };
+    assert!(matches!(cow, Cow::Borrowed("y")));
+    assert_eq!(boxed, Some(Box::new("z")));
+    assert!(json::from_str::<Untagged>(r#""a\nb""#).is_err());
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(untagged)]
+enum Nested {
+    List(Vec<json::Value>),
+}
+
+#[test]
+fn test_deeply_nested() {
+    let depth = 100_000;
+    let j = "[".repeat(depth) + &"]".repeat(depth);
+    let Nested::List(list) = json::from_str(&j).unwrap();
+    assert_eq!(list.len(), 1);
+}
@@ -0,0 +1,145 @@
+use std::fmt::Debug;
+use std::marker::PhantomData;
+
+use qser::{Deserialize, Serialize, json};
+
+mod reexport {
+    pub use qser as renamed;
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+#[serde(crate = "reexport::renamed")]
+struct CratePath {
+    a: u32,
+}
+
+#[test]
+fn test_crate_path() {
+    let value = CratePath { a: 1 };
+    assert_eq!(json::to_string(&value), r#"{"a":1}"#);
+    assert_eq!(json::from_str::<CratePath>(r#"{"a":1}"#).unwrap(), value);
+}
+
+trait Marker {
+    type Assoc: Serialize + for<'de> Deserialize<'de> + Debug + PartialEq;
+}
+
+struct Impl;
+
+impl Marker for Impl {
+    type Assoc = u32;
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+#[serde(bound = "T::Assoc: qser::Serialize + qser::de::DeserializeOwned")]
+struct Bounded<T: Marker> {
+    assoc: T::Assoc,
+    #[serde(skip)]
+    marker: PhantomData<T>,
+}
+
+impl PartialEq for Impl {
+    fn eq(&self, _: &Self) -> bool {
+        true
+    }
+}
+
+impl Debug for Impl {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        formatter.write_str("Impl")
+    }
+}
+
+#[test]
+fn test_bound() {
+    let value = Bounded::<Impl> {
+        assoc: 1,
+        marker: PhantomData,
+    };
+    assert_eq!(json::to_string(&value), r#"{"assoc":1}"#);
+    assert_eq!(
+        json::from_str::<Bounded<Impl>>(r#"{"assoc":1}"#).unwrap(),
+        value
+    );
+}
+
+mod remote {
+    #[derive(PartialEq, Debug)]
+    pub struct Point {
+        pub x: i32,
+        pub y: i32,
+    }
+
+    #[derive(PartialEq, Debug)]
+    pub struct Duration {
+        secs: u64,
+    }
+
+    impl Duration {
+        pub fn new(secs: u64) -> Self {
+            Duration { secs }
+        }
+
+        pub fn secs(&self) -> u64 {
+            self.secs
+        }
+    }
+
+    #[derive(PartialEq, Debug)]
+    pub enum Shape {
+        Circle { r: u32 },
+        Empty,
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "remote::Point")]
+struct PointDef {
+    x: i32,
+    y: i32,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "remote::Duration")]
+struct DurationDef {
+    #[serde(getter = "remote::Duration::secs")]
+    secs: u64,
+}
+
+impl From<DurationDef> for remote::Duration {
+    fn from(def: DurationDef) -> Self {
+        remote::Duration::new(def.secs)
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "remote::Shape", tag = "kind")]
+enum ShapeDef {
+    Circle { r: u32 },
+    Empty,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+struct Local {
+    #[serde(with = "PointDef")]
+    point: remote::Point,
+    #[serde(with = "DurationDef")]
+    duration: remote::Duration,
+    #[serde(with = "ShapeDef")]
+    shape: remote::Shape,
+}
+
+#[test]
+fn test_remote() {
+    let value = Local {
+        point: remote::Point { x: 1, y: 2 },
+        duration: remote::Duration::new(3),
+        shape: remote::Shape::Circle { r: 4 },
+    };
+    let j = json::to_string(&value);
+    assert_eq!(
+        j,
+        r#"{"point":{"x":1,"y":2},"duration":{"secs":3},"shape":{"kind":"Circle","r":4}}"#,
+    );
+    assert_eq!(json::from_str::<Local>(&j).unwrap(), value);
+}
@@ -0,0 +1,49 @@
+use qser::{Deserialize, Serialize, json};
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(from = "u32", into = "u32")]
+struct Celsius {
+    degrees: u32,
+}
+
+impl From<u32> for Celsius {
+    fn from(degrees: u32) -> Self {
+        Celsius { degrees }
+    }
+}
+
+impl From<Celsius> for u32 {
+    fn from(celsius: Celsius) -> Self {
+        celsius.degrees
+    }
+}
+
+#[derive(Deserialize, PartialEq, Debug)]
+#[serde(try_from = "i64")]
+struct Positive(u64);
+
+impl TryFrom<i64> for Positive {
+    type Error = &'static str;
+
+    fn try_from(n: i64) -> Result<Self, Self::Error> {
+        if n > 0 {
+            Ok(Positive(n as u64))
+        } else {
+            Err("not positive")
+        }
+    }
+}
+
+#[test]
+fn test_from_into() {
+    let celsius = Celsius { degrees: 20 };
+    assert_eq!(json::to_string(&celsius), "20");
+    assert_eq!(json::from_str::<Celsius>("20").unwrap(), celsius);
+}
+
+#[test]
+fn test_try_from() {
+    assert_eq!(json::from_str::<Positive>("3").unwrap(), Positive(3));
+    assert!(json::from_str::<Positive>("-3").is_err());
+    assert!(json::from_str::<Vec<Positive>>("[1,0]").is_err());
+}
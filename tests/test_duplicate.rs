@@ -0,0 +1,79 @@
+use std::collections::{BTreeMap, HashMap};
+
+use qser::{Deserialize, json};
+
+#[derive(Deserialize, PartialEq, Debug)]
+struct Struct {
+    a: u32,
+    #[serde(alias = "c")]
+    b: u32,
+}
+
+#[test]
+fn test_duplicate_field() {
+    assert!(json::from_str::<Struct>(r#"{"a":1,"b":2}"#).is_ok());
+    assert!(json::from_str::<Struct>(r#"{"a":1,"a":1,"b":2}"#).is_err());
+    assert!(json::from_str::<Struct>(r#"{"a":1,"b":2,"c":3}"#).is_err());
+}
+
+#[derive(Deserialize, PartialEq, Debug)]
+#[serde(untagged)]
+enum Untagged {
+    A { x: u32 },
+    Map(BTreeMap<String, u32>),
+}
+
+#[test]
+fn test_untagged() {
+    assert!(json::from_str::<Untagged>(r#"{"x":1}"#).is_ok());
+    assert!(json::from_str::<Untagged>(r#"{"x":1,"x":2}"#).is_err());
+    assert!(json::from_str::<Untagged>(r#"{"y":1,"y":2}"#).is_err());
+}
+
+#[derive(Deserialize, PartialEq, Debug)]
+#[serde(tag = "t", content = "c")]
+enum Adjacent {
+    A { x: u32 },
+    Map(BTreeMap<String, u32>),
+}
+
+#[test]
+fn test_adjacently_tagged() {
+    let j = r#"{"c":{"x":1},"t":"A"}"#;
+    assert_eq!(json::from_str::<Adjacent>(j).unwrap(), Adjacent::A { x: 1 });
+    assert!(json::from_str::<Adjacent>(r#"{"c":{"x":1,"x":2},"t":"A"}"#).is_err());
+    assert!(json::from_str::<Adjacent>(r#"{"c":{"y":1,"y":2},"t":"Map"}"#).is_err());
+    assert!(json::from_str::<Adjacent>(r#"{"t":"A","c":{"x":1,"x":2}}"#).is_err());
+}
+
+#[derive(Deserialize, PartialEq, Debug)]
+#[serde(tag = "type")]
+enum Internal {
+    A { x: u32 },
+    B { x: u32 },
+    Map(BTreeMap<String, u32>),
+}
+
+#[test]
+fn test_internally_tagged() {
+    let j = r#"{"type":"A","x":1}"#;
+    assert_eq!(json::from_str::<Internal>(j).unwrap(), Internal::A { x: 1 });
+    assert!(json::from_str::<Internal>(r#"{"type":"A","x":1,"type":"B"}"#).is_err());
+    assert!(json::from_str::<Internal>(r#"{"type":"A","type":"B","x":1}"#).is_err());
+    assert!(json::from_str::<Internal>(r#"{"x":1,"x":2,"type":"A"}"#).is_err());
+    assert!(json::from_str::<Internal>(r#"{"y":1,"y":2,"type":"Map"}"#).is_err());
+}
+
+#[derive(Deserialize, PartialEq, Debug)]
+struct Flattened {
+    id: u32,
+    #[serde(flatten)]
+    rest: HashMap<String, u32>,
+}
+
+#[test]
+fn test_flatten() {
+    let flattened: Flattened = json::from_str(r#"{"id":1,"k":1}"#).unwrap();
+    assert_eq!(flattened.rest.len(), 1);
+    assert!(json::from_str::<Flattened>(r#"{"id":1,"k":1,"k":2}"#).is_err());
+}
@@ -0,0 +1,213 @@
+use qser::{Deserialize, Serialize, json};
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+enum External {
+    Unit,
+    Newtype(u32),
+    Tuple(u32, String),
+    Struct { a: u32, b: Option<bool> },
+}
+
+#[test]
+fn test_externally_tagged() {
+    let cases = [
+        (External::Unit, r#""Unit""#),
+        (External::Newtype(1), r#"{"Newtype":1}"#),
+        (External::Tuple(1, "x".to_owned()), r#"{"Tuple":[1,"x"]}"#),
+        (
+            External::Struct { a: 1, b: None },
+            r#"{"Struct":{"a":1,"b":null}}"#,
+        ),
+    ];
+    for (value, j) in cases {
+        assert_eq!(json::to_string(&value), j);
+        assert_eq!(json::from_str::<External>(j).unwrap(), value);
+    }
+    assert_eq!(
+        json::from_str::<External>(r#"{"Unit":null}"#).unwrap(),
+        External::Unit
+    );
+    assert!(json::from_str::<External>(r#""Other""#).is_err());
+    assert!(json::from_str::<External>(r#"{"Newtype":1,"Unit":null}"#).is_err());
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+#[serde(tag = "type")]
+enum Internal {
+    Unit,
+    Struct { a: u32 },
+    Newtype(Inner),
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+struct Inner {
+    b: String,
+}
+
+#[test]
+fn test_internally_tagged() {
+    let cases = [
+        (Internal::Unit, r#"{"type":"Unit"}"#),
+        (Internal::Struct { a: 1 }, r#"{"type":"Struct","a":1}"#),
+        (
+            Internal::Newtype(Inner { b: "x".to_owned() }),
+            r#"{"type":"Newtype","b":"x"}"#,
+        ),
+    ];
+    for (value, j) in cases {
+        assert_eq!(json::to_string(&value), j);
+        assert_eq!(json::from_str::<Internal>(j).unwrap(), value);
+    }
+    let j = r#"{"a":1,"type":"Struct"}"#;
+    assert_eq!(
+        json::from_str::<Internal>(j).unwrap(),
+        Internal::Struct { a: 1 }
+    );
+    assert!(json::from_str::<Internal>(r#"{"a":1}"#).is_err());
+}
+
+#[derive(Serialize, PartialEq, Debug)]
+#[serde(tag = "type")]
+enum InternalNull {
+    Nothing(()),
+}
+
+#[test]
+fn test_internally_tagged_null() {
+    let j = json::to_string(&InternalNull::Nothing(()));
+    assert_eq!(j, r#"{"type":"Nothing"}"#);
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+#[serde(tag = "t", content = "c")]
+enum Adjacent {
+    Unit,
+    Newtype(u32),
+    Tuple(u32, u32),
+    Struct { a: u32 },
+}
+
+#[test]
+fn test_adjacently_tagged() {
+    let cases = [
+        (Adjacent::Unit, r#"{"t":"Unit"}"#),
+        (Adjacent::Newtype(1), r#"{"t":"Newtype","c":1}"#),
+        (Adjacent::Tuple(1, 2), r#"{"t":"Tuple","c":[1,2]}"#),
+        (Adjacent::Struct { a: 1 }, r#"{"t":"Struct","c":{"a":1}}"#),
+    ];
+    for (value, j) in cases {
+        assert_eq!(json::to_string(&value), j);
+        assert_eq!(json::from_str::<Adjacent>(j).unwrap(), value);
+    }
+    let j = r#"{"c":[1,2],"t":"Tuple"}"#;
+    assert_eq!(
+        json::from_str::<Adjacent>(j).unwrap(),
+        Adjacent::Tuple(1, 2)
+    );
+    assert!(json::from_str::<Adjacent>(r#"{"c":1}"#).is_err());
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+#[serde(untagged)]
+enum Untagged {
+    Struct { a: u32 },
+    Number(u64),
+    Pair(u32, String),
+    Text(String),
+    Unit,
+}
+
+#[test]
+fn test_untagged() {
+    let cases = [
+        (Untagged::Struct { a: 1 }, r#"{"a":1}"#),
+        (Untagged::Number(1), "1"),
+        (Untagged::Pair(1, "x".to_owned()), r#"[1,"x"]"#),
+        (Untagged::Text("x".to_owned()), r#""x""#),
+        (Untagged::Unit, "null"),
+    ];
+    for (value, j) in cases {
+        assert_eq!(json::to_string(&value), j);
+        assert_eq!(json::from_str::<Untagged>(j).unwrap(), value);
+    }
+    assert!(json::from_str::<Untagged>("true").is_err());
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+enum Skipped {
+    Visible(u32),
+    #[serde(skip_deserializing)]
+    Hidden(u32),
+}
+
+#[test]
+fn test_skip_deserializing() {
+    assert_eq!(json::to_string(&Skipped::Hidden(1)), r#"{"Hidden":1}"#);
+    let j = r#"{"Visible":1}"#;
+    assert_eq!(json::from_str::<Skipped>(j).unwrap(), Skipped::Visible(1));
+    assert!(json::from_str::<Skipped>(r#"{"Hidden":1}"#).is_err());
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+enum WithOther {
+    Known,
+    #[serde(other)]
+    Unknown,
+}
+
+#[test]
+fn test_other() {
+    assert_eq!(
+        json::from_str::<WithOther>(r#""Known""#).unwrap(),
+        WithOther::Known
+    );
+    assert_eq!(
+        json::from_str::<WithOther>(r#""Else""#).unwrap(),
+        WithOther::Unknown
+    );
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+enum Generic<T, U> {
+    A(T),
+    B { u: U },
+    C(Vec<T>, Option<U>),
+}
+
+#[test]
+fn test_generic() {
+    let value = Generic::<u32, String>::C(vec![1, 2], Some("x".to_owned()));
+    let j = json::to_string(&value);
+    assert_eq!(j, r#"{"C":[[1,2],"x"]}"#);
+    assert_eq!(json::from_str::<Generic<u32, String>>(&j).unwrap(), value);
+    let value = Generic::<u32, bool>::B { u: true };
+    assert_eq!(
+        json::from_str::<Generic<u32, bool>>(r#"{"B":{"u":true}}"#).unwrap(),
+        value
+    );
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+#[qser(repr = "int")]
+enum Level {
+    Low = 1,
+    Middle,
+    High = 10,
+    Negative = -1,
+}
+
+#[test]
+fn test_repr_int() {
+    let cases = [
+        (Level::Low, "1"),
+        (Level::Middle, "2"),
+        (Level::High, "10"),
+        (Level::Negative, "-1"),
+    ];
+    for (value, j) in cases {
+        assert_eq!(json::to_string(&value), j);
+        assert_eq!(json::from_str::<Level>(j).unwrap(), value);
+    }
+    assert!(json::from_str::<Level>("3").is_err());
+    assert!(json::from_str::<Level>(r#""Low""#).is_err());
+}
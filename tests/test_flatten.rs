@@ -0,0 +1,76 @@
+use std::collections::BTreeMap;
+
+use qser::{Deserialize, Serialize, json};
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+struct Outer {
+    id: u32,
+    #[serde(flatten)]
+    inner: Inner,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+struct Inner {
+    a: u32,
+    b: String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+struct CatchAll {
+    id: u32,
+    #[serde(flatten)]
+    rest: BTreeMap<String, json::Value>,
+}
+
+#[test]
+fn test_flatten_struct() {
+    let outer = Outer {
+        id: 1,
+        inner: Inner {
+            a: 2,
+            b: "b".to_owned(),
+        },
+    };
+    let j = json::to_string(&outer);
+    assert_eq!(j, r#"{"id":1,"a":2,"b":"b"}"#);
+    assert_eq!(json::from_str::<Outer>(&j).unwrap(), outer);
+    assert_eq!(
+        json::from_str::<Outer>(r#"{"b":"b","id":1,"a":2}"#).unwrap(),
+        outer
+    );
+}
+
+#[test]
+fn test_flatten_map() {
+    let j = r#"{"id":1,"x":[true],"y":null}"#;
+    let catch_all: CatchAll = json::from_str(j).unwrap();
+    assert_eq!(catch_all.id, 1);
+    assert_eq!(catch_all.rest.len(), 2);
+    assert_eq!(json::to_string(&catch_all), j);
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+struct Optional {
+    id: u32,
+    #[serde(flatten)]
+    inner: Option<Inner>,
+}
+
+#[test]
+fn test_flatten_option() {
+    let optional = Optional { id: 1, inner: None };
+    let j = json::to_string(&optional);
+    assert_eq!(j, r#"{"id":1}"#);
+    assert_eq!(json::from_str::<Optional>(&j).unwrap(), optional);
+    let optional = Optional {
+        id: 1,
+        inner: Some(Inner {
+            a: 2,
+            b: "b".to_owned(),
+        }),
+    };
+    let j = json::to_string(&optional);
+    assert_eq!(j, r#"{"id":1,"a":2,"b":"b"}"#);
+    assert_eq!(json::from_str::<Optional>(&j).unwrap(), optional);
+    assert!(json::from_str::<Optional>(r#"{"id":1,"a":2}"#).is_err());
+}
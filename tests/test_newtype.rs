@@ -0,0 +1,83 @@
+use qser::{Deserialize, Serialize, json};
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+struct Newtype(u32);
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+#[serde(transparent)]
+struct Transparent {
+    inner: String,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+struct Tuple(u32, String, Option<bool>);
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+struct Unit;
+
+#[derive(Deserialize, PartialEq, Debug)]
+#[serde(default)]
+struct TupleDefault(u32, u32, String);
+
+impl Default for TupleDefault {
+    fn default() -> Self {
+        TupleDefault(7, 8, "nine".to_owned())
+    }
+}
+
+#[derive(Deserialize, PartialEq, Debug)]
+struct FieldDefault(
+    u32,
+    #[serde(default)] u32,
+    #[serde(default = "nine")] String,
+);
+
+fn nine() -> String {
+    "nine".to_owned()
+}
+
+#[test]
+fn test_newtype() {
+    assert_eq!(json::to_string(&Newtype(1)), "1");
+    assert_eq!(json::from_str::<Newtype>("1").unwrap(), Newtype(1));
+
+    let transparent = Transparent {
+        inner: "x".to_owned(),
+    };
+    assert_eq!(json::to_string(&transparent), r#""x""#);
+    assert_eq!(
+        json::from_str::<Transparent>(r#""x""#).unwrap(),
+        transparent
+    );
+}
+
+#[test]
+fn test_tuple() {
+    let tuple = Tuple(1, "two".to_owned(), None);
+    let j = json::to_string(&tuple);
+    assert_eq!(j, r#"[1,"two",null]"#);
+    assert_eq!(json::from_str::<Tuple>(&j).unwrap(), tuple);
+    assert!(json::from_str::<Tuple>(r#"[1,"two"]"#).is_err());
+    assert!(json::from_str::<Tuple>(r#"[1,"two",null,4]"#).is_err());
+}
+
+#[test]
+fn test_tuple_default() {
+    let tuple: TupleDefault = json::from_str("[1]").unwrap();
+    assert_eq!(tuple, TupleDefault(1, 8, "nine".to_owned()));
+    let tuple: TupleDefault = json::from_str("[]").unwrap();
+    assert_eq!(tuple, TupleDefault(7, 8, "nine".to_owned()));
+
+    let tuple: FieldDefault = json::from_str("[1]").unwrap();
+    assert_eq!(tuple, FieldDefault(1, 0, "nine".to_owned()));
+    let tuple: FieldDefault = json::from_str(r#"[1,2,"x"]"#).unwrap();
+    assert_eq!(tuple, FieldDefault(1, 2, "x".to_owned()));
+    assert!(json::from_str::<FieldDefault>("[]").is_err());
+}
+
+#[test]
+fn test_unit() {
+    assert_eq!(json::to_string(&Unit), "null");
+    assert_eq!(json::from_str::<Unit>("null").unwrap(), Unit);
+    assert!(json::from_str::<Unit>("0").is_err());
+}
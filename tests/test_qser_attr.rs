@@ -0,0 +1,107 @@
+use qser::{Deserialize, Serialize, json};
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+#[qser(rename_all = "UPPERCASE")]
+struct Namespaced {
+    #[qser(rename = "renamed")]
+    a: u32,
+    b: u32,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+struct Precedence {
+    #[serde(rename = "serde")]
+    #[qser(rename = "qser")]
+    a: u32,
+}
+
+#[test]
+fn test_namespace() {
+    let value = Namespaced { a: 1, b: 2 };
+    let j = json::to_string(&value);
+    assert_eq!(j, r#"{"renamed":1,"B":2}"#);
+    assert_eq!(json::from_str::<Namespaced>(&j).unwrap(), value);
+}
+
+#[test]
+fn test_precedence() {
+    let value = Precedence { a: 1 };
+    assert_eq!(json::to_string(&value), r#"{"qser":1}"#);
+    assert_eq!(
+        json::from_str::<Precedence>(r#"{"qser":1}"#).unwrap(),
+        value
+    );
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+#[serde(tag = "t")]
+#[qser(untagged)]
+enum Retagged {
+    A { a: u32 },
+}
+
+#[test]
+fn test_tag_style_precedence() {
+    let value = Retagged::A { a: 1 };
+    assert_eq!(json::to_string(&value), r#"{"a":1}"#);
+    assert_eq!(json::from_str::<Retagged>(r#"{"a":1}"#).unwrap(), value);
+}
+
+mod doubled {
+    use qser::de::{Deserialize, Visitor};
+    use qser::ser::Fragment;
+
+    pub fn serialize(n: &u32) -> Fragment<'_> {
+        Fragment::U64(u64::from(*n) * 2)
+    }
+
+    pub fn deserialize<'de>(out: &mut Option<u32>) -> &mut dyn Visitor<'de> {
+        u32::begin(out)
+    }
+}
+
+fn tripled(n: &u32) -> qser::ser::Fragment<'_> {
+    qser::ser::Fragment::U64(u64::from(*n) * 3)
+}
+
+fn always(_: &u32) -> bool {
+    true
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+struct Hooks {
+    #[serde(with = "doubled")]
+    #[qser(serialize_with = "tripled")]
+    a: u32,
+    #[serde(skip_serializing_if = "always")]
+    #[qser(skip_deserializing)]
+    b: u32,
+    #[serde(deserialize_with = "nonexistent")]
+    #[qser(with = "doubled")]
+    c: u32,
+    #[serde(rename = "serde")]
+    #[qser(rename(serialize = "qser"))]
+    d: u32,
+}
+
+#[test]
+fn test_hook_precedence() {
+    let value = Hooks {
+        a: 1,
+        b: 2,
+        c: 3,
+        d: 4,
+    };
+    assert_eq!(json::to_string(&value), r#"{"a":3,"c":6,"qser":4}"#);
+    let j = r#"{"a":1,"b":2,"c":3,"serde":4}"#;
+    assert_eq!(
+        json::from_str::<Hooks>(j).unwrap(),
+        Hooks {
+            a: 1,
+            b: 0,
+            c: 3,
+            d: 4,
+        },
+    );
+    assert!(json::from_str::<Hooks>(r#"{"a":1,"c":3,"qser":4}"#).is_err());
+}
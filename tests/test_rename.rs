@@ -0,0 +1,67 @@
+use qser::{Deserialize, Serialize, json};
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+#[serde(rename_all = "camelCase")]
+struct Camel {
+    first_field: u32,
+    #[serde(rename = "SECOND")]
+    second_field: u32,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+#[serde(rename_all = "kebab-case")]
+enum Kebab {
+    FirstVariant,
+    #[serde(rename = "two")]
+    SecondVariant,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+struct Split {
+    #[serde(rename(serialize = "out", deserialize = "in"))]
+    field: u32,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+#[serde(rename_all(serialize = "UPPERCASE", deserialize = "snake_case"))]
+struct SplitAll {
+    some_field: u32,
+}
+
+#[test]
+fn test_rename_all() {
+    let camel = Camel {
+        first_field: 1,
+        second_field: 2,
+    };
+    let j = json::to_string(&camel);
+    assert_eq!(j, r#"{"firstField":1,"SECOND":2}"#);
+    assert_eq!(json::from_str::<Camel>(&j).unwrap(), camel);
+
+    assert_eq!(json::to_string(&Kebab::FirstVariant), r#""first-variant""#);
+    assert_eq!(json::to_string(&Kebab::SecondVariant), r#""two""#);
+    assert_eq!(
+        json::from_str::<Kebab>(r#""two""#).unwrap(),
+        Kebab::SecondVariant
+    );
+}
+
+#[test]
+fn test_split_rename() {
+    assert_eq!(json::to_string(&Split { field: 1 }), r#"{"out":1}"#);
+    assert_eq!(
+        json::from_str::<Split>(r#"{"in":1}"#).unwrap(),
+        Split { field: 1 }
+    );
+    assert!(json::from_str::<Split>(r#"{"out":1}"#).is_err());
+
+    assert_eq!(
+        json::to_string(&SplitAll { some_field: 1 }),
+        r#"{"SOME_FIELD":1}"#
+    );
+    let j = r#"{"some_field":1}"#;
+    assert_eq!(
+        json::from_str::<SplitAll>(j).unwrap(),
+        SplitAll { some_field: 1 }
+    );
+}
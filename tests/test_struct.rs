@@ -0,0 +1,166 @@
+use qser::{Deserialize, Serialize, json};
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+struct Example {
+    x: u32,
+    s: String,
+    opt: Option<i64>,
+}
+
+#[test]
+fn test_round_trip() {
+    let example = Example {
+        x: 1,
+        s: "two".to_owned(),
+        opt: None,
+    };
+    let j = json::to_string(&example);
+    assert_eq!(j, r#"{"x":1,"s":"two","opt":null}"#);
+    assert_eq!(json::from_str::<Example>(&j).unwrap(), example);
+}
+
+#[test]
+fn test_missing_field() {
+    assert!(json::from_str::<Example>(r#"{"x":1,"opt":null}"#).is_err());
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Default)]
+struct Defaults {
+    #[serde(default)]
+    a: u32,
+    #[serde(default = "seven")]
+    b: u32,
+    c: u32,
+}
+
+fn seven() -> u32 {
+    7
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+#[serde(default)]
+struct ContainerDefault {
+    a: u32,
+    b: String,
+    c: Option<u32>,
+}
+
+impl Default for ContainerDefault {
+    fn default() -> Self {
+        ContainerDefault {
+            a: 9,
+            b: "nine".to_owned(),
+            c: Some(9),
+        }
+    }
+}
+
+#[derive(Deserialize, PartialEq, Debug)]
+struct OptionDefault {
+    #[serde(default = "some_seven")]
+    a: Option<u32>,
+}
+
+fn some_seven() -> Option<u32> {
+    Some(7)
+}
+
+#[test]
+fn test_default() {
+    let d: Defaults = json::from_str(r#"{"c":3}"#).unwrap();
+    assert_eq!(d, Defaults { a: 0, b: 7, c: 3 });
+    let d: Defaults = json::from_str(r#"{"a":1,"b":2,"c":3}"#).unwrap();
+    assert_eq!(d, Defaults { a: 1, b: 2, c: 3 });
+
+    let d: ContainerDefault = json::from_str(r#"{"a":1}"#).unwrap();
+    assert_eq!(
+        d,
+        ContainerDefault {
+            a: 1,
+            b: "nine".to_owned(),
+            c: Some(9),
+        },
+    );
+    let d: ContainerDefault = json::from_str(r#"{"c":null}"#).unwrap();
+    assert_eq!(d.c, None);
+
+    let d: OptionDefault = json::from_str("{}").unwrap();
+    assert_eq!(d, OptionDefault { a: Some(7) });
+    let d: OptionDefault = json::from_str(r#"{"a":null}"#).unwrap();
+    assert_eq!(d, OptionDefault { a: None });
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+struct Skips {
+    #[serde(skip)]
+    skipped: u32,
+    #[serde(skip_serializing)]
+    write_only: u32,
+    #[serde(skip_deserializing)]
+    read_only: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    maybe: Option<u32>,
+}
+
+#[test]
+fn test_skip() {
+    let skips = Skips {
+        skipped: 1,
+        write_only: 2,
+        read_only: 3,
+        maybe: None,
+    };
+    assert_eq!(json::to_string(&skips), r#"{"read_only":3}"#);
+    let skips = Skips {
+        maybe: Some(4),
+        ..skips
+    };
+    assert_eq!(json::to_string(&skips), r#"{"read_only":3,"maybe":4}"#);
+
+    let j = r#"{"skipped":1,"write_only":2,"read_only":3,"maybe":4}"#;
+    let skips: Skips = json::from_str(j).unwrap();
+    assert_eq!(
+        skips,
+        Skips {
+            skipped: 0,
+            write_only: 2,
+            read_only: 0,
+            maybe: Some(4),
+        },
+    );
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+#[serde(deny_unknown_fields)]
+struct Strict {
+    a: u32,
+}
+
+#[test]
+fn test_deny_unknown_fields() {
+    assert_eq!(
+        json::from_str::<Strict>(r#"{"a":1}"#).unwrap(),
+        Strict { a: 1 }
+    );
+    assert!(json::from_str::<Strict>(r#"{"a":1,"b":2}"#).is_err());
+    assert_eq!(
+        json::from_str::<Example>(r#"{"x":1,"s":"","opt":null,"y":[{}]}"#)
+            .unwrap()
+            .x,
+        1
+    );
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+struct Aliased {
+    #[serde(alias = "old_name", alias = "older_name")]
+    name: u32,
+}
+
+#[test]
+fn test_alias() {
+    for j in [r#"{"name":1}"#, r#"{"old_name":1}"#, r#"{"older_name":1}"#] {
+        assert_eq!(json::from_str::<Aliased>(j).unwrap(), Aliased { name: 1 });
+    }
+    assert_eq!(json::to_string(&Aliased { name: 1 }), r#"{"name":1}"#);
+}
@@ -0,0 +1,50 @@
+use qser::{Deserialize, Serialize, json};
+
+mod as_string {
+    use qser::de::Visitor;
+    use qser::ser::Fragment;
+    use qser::{Result, make_place};
+
+    make_place!(Place);
+
+    impl Visitor<'_> for Place<u32> {
+        fn string(&mut self, s: &str) -> Result<()> {
+            self.out = Some(s.parse().map_err(|_| qser::Error)?);
+            Ok(())
+        }
+    }
+
+    pub fn serialize(n: &u32) -> Fragment<'_> {
+        Fragment::Str(n.to_string().into())
+    }
+
+    pub fn deserialize<'de>(out: &mut Option<u32>) -> &mut dyn Visitor<'de> {
+        Place::new(out)
+    }
+}
+
+mod twice {
+    use qser::ser::Fragment;
+
+    pub fn serialize(n: &u32) -> Fragment<'_> {
+        Fragment::U64(u64::from(*n) * 2)
+    }
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+struct Codecs {
+    #[serde(with = "as_string")]
+    a: u32,
+    #[serde(serialize_with = "twice::serialize")]
+    b: u32,
+    #[serde(deserialize_with = "as_string::deserialize")]
+    c: u32,
+}
+
+#[test]
+fn test_with() {
+    let codecs = Codecs { a: 1, b: 2, c: 3 };
+    assert_eq!(json::to_string(&codecs), r#"{"a":"1","b":4,"c":3}"#);
+    let j = r#"{"a":"1","b":2,"c":"3"}"#;
+    assert_eq!(json::from_str::<Codecs>(j).unwrap(), codecs);
+}
@@ -0,0 +1,9 @@
+use qser::Deserialize;
+
+#[derive(Deserialize)]
+struct Struct {
+    #[serde(borrow)]
+    s: String,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use qser::Deserialize;
+
+#[derive(Deserialize)]
+#[serde(from = "u8", try_from = "u8")]
+struct Struct {
+    n: u8,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use qser::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "t", untagged)]
+enum Enum {
+    A(u8),
+}
+
+fn main() {}
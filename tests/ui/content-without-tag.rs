@@ -0,0 +1,9 @@
+use qser::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize)]
+#[serde(content = "c")]
+enum Enum {
+    A(u8),
+}
+
+fn main() {}
@@ -0,0 +1,18 @@
+use qser::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize)]
+struct Inner {
+    n: u8,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Outer {
+    #[serde(flatten, rename = "a", alias = "b", default)]
+    inner: Inner,
+    #[serde(flatten, with = "m")]
+    with: Inner,
+    #[serde(flatten, serialize_with = "f", deserialize_with = "g")]
+    hooks: Inner,
+}
+
+fn main() {}
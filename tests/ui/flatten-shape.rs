@@ -0,0 +1,25 @@
+use std::collections::BTreeMap;
+
+use qser::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize)]
+struct Scalar {
+    #[serde(flatten)]
+    n: u32,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Sequence {
+    #[serde(flatten)]
+    list: Vec<u32>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Twice {
+    #[serde(flatten)]
+    a: BTreeMap<String, u32>,
+    #[serde(flatten)]
+    b: BTreeMap<String, u32>,
+}
+
+fn main() {}
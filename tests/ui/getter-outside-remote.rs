@@ -0,0 +1,9 @@
+use qser::Serialize;
+
+#[derive(Serialize)]
+struct Struct {
+    #[serde(getter = "Struct::n")]
+    n: u8,
+}
+
+fn main() {}
@@ -0,0 +1,24 @@
+use qser::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "type")]
+enum Scalar {
+    A(u64),
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "type")]
+enum Field {
+    A {
+        #[serde(rename = "type")]
+        kind: u8,
+    },
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "Kind", rename_all_fields = "PascalCase")]
+enum Renamed {
+    A { kind: u8 },
+}
+
+fn main() {}
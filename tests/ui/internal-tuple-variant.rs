@@ -0,0 +1,9 @@
+use qser::Deserialize;
+
+#[derive(Deserialize)]
+#[serde(tag = "type")]
+enum Enum {
+    A(u8, u8),
+}
+
+fn main() {}
@@ -0,0 +1,15 @@
+use qser::Serialize;
+
+#[derive(Serialize)]
+#[serde(rename_all = "SHOUTY")]
+struct Struct {
+    n: u8,
+}
+
+#[derive(Serialize)]
+#[qser(repr = "u8")]
+enum Enum {
+    A,
+}
+
+fn main() {}
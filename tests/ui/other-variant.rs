@@ -0,0 +1,10 @@
+use qser::Deserialize;
+
+#[derive(Deserialize)]
+enum Enum {
+    A,
+    #[serde(other)]
+    B(u8),
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use qser::Serialize;
+
+#[derive(Serialize)]
+#[qser(repr = "int")]
+enum Enum {
+    A,
+    B(u8),
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use qser::Serialize;
+
+#[derive(Serialize)]
+#[serde(repr = "int")]
+enum Enum {
+    A,
+}
+
+fn main() {}
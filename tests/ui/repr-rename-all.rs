@@ -0,0 +1,10 @@
+use qser::Serialize;
+
+#[derive(Serialize)]
+#[qser(repr = "int")]
+#[serde(rename_all = "lowercase")]
+enum Enum {
+    A,
+}
+
+fn main() {}
@@ -0,0 +1,14 @@
+use qser::Serialize;
+
+#[derive(Serialize)]
+enum Enum {
+    A,
+    #[serde(skip)]
+    B,
+    #[serde(skip_serializing)]
+    C,
+    #[serde(skip_serializing_if = "is_d")]
+    D,
+}
+
+fn main() {}
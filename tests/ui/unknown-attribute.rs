@@ -0,0 +1,9 @@
+use qser::Serialize;
+
+#[derive(Serialize)]
+struct Struct {
+    #[serde(frobnicate)]
+    n: u8,
+}
+
+fn main() {}
@@ -0,0 +1,31 @@
+use qser::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename = "Renamed", tag = "type")]
+struct Struct {
+    n: u8,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+enum Enum {
+    A(u8),
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(transparent)]
+enum Other {
+    A(u8),
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+struct Tuple(u8, u8);
+
+#[derive(Serialize, Deserialize)]
+#[serde(transparent, default)]
+struct Transparent {
+    n: u8,
+}
+
+fn main() {}
@@ -0,0 +1,16 @@
+use qser::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize)]
+struct Tuple(#[serde(rename = "a")] u8, #[serde(alias = "b")] u8);
+
+#[derive(Serialize, Deserialize)]
+enum Enum {
+    A(#[serde(flatten)] Inner, u8),
+}
+
+#[derive(Serialize, Deserialize)]
+struct Inner {
+    n: u8,
+}
+
+fn main() {}
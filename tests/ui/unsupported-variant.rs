@@ -0,0 +1,13 @@
+use qser::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize)]
+enum Enum {
+    #[serde(with = "nonexistent")]
+    A(u8),
+    #[serde(untagged)]
+    B(u8),
+    #[serde(rename_all = "camelCase")]
+    C,
+}
+
+fn main() {}
@@ -0,0 +1,16 @@
+use qser::Serialize;
+
+#[derive(Serialize)]
+#[serde(flatten)]
+struct Struct {
+    #[serde(deny_unknown_fields)]
+    n: u8,
+}
+
+#[derive(Serialize)]
+enum Enum {
+    #[serde(alias = "b")]
+    A,
+}
+
+fn main() {}